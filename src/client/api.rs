@@ -0,0 +1,301 @@
+//! The `OrcaApi` trait, the endpoint surface of the Orca Public API.
+//!
+//! Code that depends on `impl OrcaApi` instead of `OrcaClient` can be tested against a fake
+//! implementation that returns canned models, without an HTTP server.
+
+use crate::client::client::{GetPoolsParams, OrcaClient, SearchPoolsParams, SearchTokensParams};
+use crate::client::transport::{HttpTransport, MaybeSend, MaybeSync};
+use crate::error::error::OrcaError;
+use crate::models::models::{
+    CirculatingSupplyResponse, Cursor, LockInfo, Paginated, ProtocolInfo, Token, TokenInfo,
+    TotalSupplyResponse, Whirlpool,
+};
+use std::future::Future;
+
+/// One method per endpoint of the Orca Public API.
+///
+/// `OrcaClient` is the implementation that talks to the API; the helpers built on top of the
+/// endpoints, such as `pools_stream` or `get_pool_single`, stay inherent to it.
+pub trait OrcaApi: MaybeSend + MaybeSync {
+    /// Returns general information about the Orca protocol.
+    fn get_protocol_info(
+        &self,
+        chain: &str,
+    ) -> impl Future<Output = Result<ProtocolInfo, OrcaError>> + MaybeSend;
+
+    /// Returns detailed information about the Orca token.
+    fn get_token_info(
+        &self,
+        chain: &str,
+    ) -> impl Future<Output = Result<TokenInfo, OrcaError>> + MaybeSend;
+
+    /// Returns the circulating supply of the protocol's token.
+    fn get_circulating_supply(
+        &self,
+        chain: &str,
+    ) -> impl Future<Output = Result<CirculatingSupplyResponse, OrcaError>> + MaybeSend;
+
+    /// Returns the total supply of the protocol's token.
+    fn get_total_supply(
+        &self,
+        chain: &str,
+    ) -> impl Future<Output = Result<TotalSupplyResponse, OrcaError>> + MaybeSend;
+
+    /// Returns a paginated list of tokens with optional filtering and sorting.
+    #[allow(clippy::too_many_arguments)]
+    fn get_tokens(
+        &self,
+        chain: &str,
+        next: Option<&Cursor>,
+        previous: Option<&Cursor>,
+        size: Option<u32>,
+        sort_by: Option<&str>,
+        sort_direction: Option<&str>,
+        tokens: Option<&str>,
+    ) -> impl Future<Output = Result<Paginated<Token>, OrcaError>> + MaybeSend;
+
+    /// Returns a page of tokens matching `params.q`, with paging and sorting options.
+    fn search_tokens_with_params(
+        &self,
+        chain: &str,
+        params: SearchTokensParams<'_>,
+    ) -> impl Future<Output = Result<Paginated<Token>, OrcaError>> + MaybeSend;
+
+    /// Returns detailed information for a specific token identified by its mint address.
+    fn get_token(
+        &self,
+        chain: &str,
+        mint_address: &str,
+    ) -> impl Future<Output = Result<Paginated<Token>, OrcaError>> + MaybeSend;
+
+    /// Returns the locked liquidity for a given whirlpool.
+    fn get_lock_info(
+        &self,
+        chain: &str,
+        address: &str,
+    ) -> impl Future<Output = Result<Vec<LockInfo>, OrcaError>> + MaybeSend;
+
+    /// Lists whirlpools with optional filtering and pagination.
+    fn get_pools(
+        &self,
+        chain: &str,
+        params: GetPoolsParams<'_>,
+    ) -> impl Future<Output = Result<Paginated<Whirlpool>, OrcaError>> + MaybeSend;
+
+    /// Searches for whirlpools.
+    fn search_pools(
+        &self,
+        chain: &str,
+        params: SearchPoolsParams<'_>,
+    ) -> impl Future<Output = Result<Paginated<Whirlpool>, OrcaError>> + MaybeSend;
+
+    /// Returns whirlpool data by address.
+    fn get_pool(
+        &self,
+        chain: &str,
+        address: &str,
+    ) -> impl Future<Output = Result<Paginated<Whirlpool>, OrcaError>> + MaybeSend;
+}
+
+impl<H: HttpTransport> OrcaApi for OrcaClient<H> {
+    async fn get_protocol_info(&self, chain: &str) -> Result<ProtocolInfo, OrcaError> {
+        OrcaClient::get_protocol_info(self, chain).await
+    }
+
+    async fn get_token_info(&self, chain: &str) -> Result<TokenInfo, OrcaError> {
+        OrcaClient::get_token_info(self, chain).await
+    }
+
+    async fn get_circulating_supply(
+        &self,
+        chain: &str,
+    ) -> Result<CirculatingSupplyResponse, OrcaError> {
+        OrcaClient::get_circulating_supply(self, chain).await
+    }
+
+    async fn get_total_supply(&self, chain: &str) -> Result<TotalSupplyResponse, OrcaError> {
+        OrcaClient::get_total_supply(self, chain).await
+    }
+
+    async fn get_tokens(
+        &self,
+        chain: &str,
+        next: Option<&Cursor>,
+        previous: Option<&Cursor>,
+        size: Option<u32>,
+        sort_by: Option<&str>,
+        sort_direction: Option<&str>,
+        tokens: Option<&str>,
+    ) -> Result<Paginated<Token>, OrcaError> {
+        OrcaClient::get_tokens(
+            self,
+            chain,
+            next,
+            previous,
+            size,
+            sort_by,
+            sort_direction,
+            tokens,
+        )
+        .await
+    }
+
+    async fn search_tokens_with_params(
+        &self,
+        chain: &str,
+        params: SearchTokensParams<'_>,
+    ) -> Result<Paginated<Token>, OrcaError> {
+        OrcaClient::search_tokens_with_params(self, chain, params).await
+    }
+
+    async fn get_token(
+        &self,
+        chain: &str,
+        mint_address: &str,
+    ) -> Result<Paginated<Token>, OrcaError> {
+        OrcaClient::get_token(self, chain, mint_address).await
+    }
+
+    async fn get_lock_info(&self, chain: &str, address: &str) -> Result<Vec<LockInfo>, OrcaError> {
+        OrcaClient::get_lock_info(self, chain, address).await
+    }
+
+    async fn get_pools(
+        &self,
+        chain: &str,
+        params: GetPoolsParams<'_>,
+    ) -> Result<Paginated<Whirlpool>, OrcaError> {
+        OrcaClient::get_pools(self, chain, params).await
+    }
+
+    async fn search_pools(
+        &self,
+        chain: &str,
+        params: SearchPoolsParams<'_>,
+    ) -> Result<Paginated<Whirlpool>, OrcaError> {
+        OrcaClient::search_pools(self, chain, params).await
+    }
+
+    async fn get_pool(
+        &self,
+        chain: &str,
+        address: &str,
+    ) -> Result<Paginated<Whirlpool>, OrcaError> {
+        OrcaClient::get_pool(self, chain, address).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::fixtures::{self, SOL_USDC_POOL};
+    use mockito::mock;
+
+    /// A fake API serving a single pool from fixtures.
+    struct MockOrcaApi;
+
+    fn unexpected<T>() -> Result<T, OrcaError> {
+        Err(OrcaError::Validation("unexpected call".to_string()))
+    }
+
+    impl OrcaApi for MockOrcaApi {
+        async fn get_protocol_info(&self, _: &str) -> Result<ProtocolInfo, OrcaError> {
+            unexpected()
+        }
+
+        async fn get_token_info(&self, _: &str) -> Result<TokenInfo, OrcaError> {
+            unexpected()
+        }
+
+        async fn get_circulating_supply(
+            &self,
+            _: &str,
+        ) -> Result<CirculatingSupplyResponse, OrcaError> {
+            unexpected()
+        }
+
+        async fn get_total_supply(&self, _: &str) -> Result<TotalSupplyResponse, OrcaError> {
+            unexpected()
+        }
+
+        async fn get_tokens(
+            &self,
+            _: &str,
+            _: Option<&Cursor>,
+            _: Option<&Cursor>,
+            _: Option<u32>,
+            _: Option<&str>,
+            _: Option<&str>,
+            _: Option<&str>,
+        ) -> Result<Paginated<Token>, OrcaError> {
+            unexpected()
+        }
+
+        async fn search_tokens_with_params(
+            &self,
+            _: &str,
+            _: SearchTokensParams<'_>,
+        ) -> Result<Paginated<Token>, OrcaError> {
+            unexpected()
+        }
+
+        async fn get_token(&self, _: &str, _: &str) -> Result<Paginated<Token>, OrcaError> {
+            unexpected()
+        }
+
+        async fn get_lock_info(&self, _: &str, _: &str) -> Result<Vec<LockInfo>, OrcaError> {
+            unexpected()
+        }
+
+        async fn get_pools(
+            &self,
+            _: &str,
+            _: GetPoolsParams<'_>,
+        ) -> Result<Paginated<Whirlpool>, OrcaError> {
+            unexpected()
+        }
+
+        async fn search_pools(
+            &self,
+            _: &str,
+            _: SearchPoolsParams<'_>,
+        ) -> Result<Paginated<Whirlpool>, OrcaError> {
+            unexpected()
+        }
+
+        async fn get_pool(&self, _: &str, _: &str) -> Result<Paginated<Whirlpool>, OrcaError> {
+            Ok(serde_json::from_str(&fixtures::paginated(vec![
+                fixtures::whirlpool_json(),
+            ]))?)
+        }
+    }
+
+    async fn pool_fee_rate(api: &impl OrcaApi, address: &str) -> Result<u32, OrcaError> {
+        Ok(api
+            .get_pool("solana", address)
+            .await?
+            .into_first()?
+            .fee_rate)
+    }
+
+    #[tokio::test]
+    async fn test_mock_implementation() {
+        assert_eq!(
+            pool_fee_rate(&MockOrcaApi, SOL_USDC_POOL).await.unwrap(),
+            400
+        );
+        assert!(MockOrcaApi.get_protocol_info("solana").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_client_implementation() {
+        let _m = mock("GET", format!("/solana/pools/{}", SOL_USDC_POOL).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![fixtures::whirlpool_json()]))
+            .create();
+
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        assert_eq!(pool_fee_rate(&client, SOL_USDC_POOL).await.unwrap(), 400);
+    }
+}
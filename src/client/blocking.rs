@@ -0,0 +1,315 @@
+//! A synchronous client for the Orca Public API, enabled by the `blocking` feature.
+//!
+//! It mirrors the endpoint methods of the async `OrcaClient` on top of
+//! `reqwest::blocking::Client`, sharing the same URL construction and models. Apart from
+//! `empty_paginated_is_error`, the builder options such as retries, caching and rate
+//! limiting are only available on the async client.
+//!
+//! Like `reqwest::blocking`, this client must not be used from within an async runtime.
+
+use crate::client::client::{
+    GetPoolsParams, SearchPoolsParams, SearchTokensParams, DEFAULT_API_VERSION, DEFAULT_HOST,
+};
+use crate::client::endpoints;
+use crate::client::transport::{check_json_content_type, parse_retry_after};
+use crate::error::error::OrcaError;
+use crate::models::models::{
+    CirculatingSupplyResponse, Cursor, LockInfo, Paginated, ProtocolInfo, Token, TokenInfo,
+    TotalSupplyResponse, Whirlpool,
+};
+use reqwest::blocking::Client;
+use reqwest::header::{CONTENT_TYPE, RETRY_AFTER};
+use reqwest::Url;
+use serde::de::DeserializeOwned;
+use std::time::SystemTime;
+
+/// A blocking client for interacting with the Orca Public API.
+#[derive(Clone)]
+pub struct OrcaClient {
+    client: Client,
+    base_url: String,
+    empty_paginated_is_error: bool,
+}
+
+impl Default for OrcaClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OrcaClient {
+    /// Creates a new blocking `OrcaClient` with the default base URL.
+    pub fn new() -> Self {
+        Self::with_base_url(&format!("{}/{}", DEFAULT_HOST, DEFAULT_API_VERSION))
+    }
+
+    /// Creates a new blocking `OrcaClient` with a custom base URL. Trailing slashes are
+    /// ignored.
+    pub fn with_base_url(base_url: &str) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            empty_paginated_is_error: false,
+        }
+    }
+
+    /// Makes `get_pool` and `get_token` return `OrcaError::NotFound` when the response has an
+    /// empty `data` array, instead of an empty page. Off by default.
+    pub fn empty_paginated_is_error(mut self, enabled: bool) -> Self {
+        self.empty_paginated_is_error = enabled;
+        self
+    }
+
+    /// Returns general information about the Orca protocol.
+    pub fn get_protocol_info(&self, chain: &str) -> Result<ProtocolInfo, OrcaError> {
+        self.get_json(endpoints::protocol(&self.base_url, chain)?)
+    }
+
+    /// Returns detailed information about the Orca token.
+    pub fn get_token_info(&self, chain: &str) -> Result<TokenInfo, OrcaError> {
+        self.get_json(endpoints::token_info(&self.base_url, chain)?)
+    }
+
+    /// Returns the circulating supply of the protocol's token.
+    pub fn get_circulating_supply(
+        &self,
+        chain: &str,
+    ) -> Result<CirculatingSupplyResponse, OrcaError> {
+        self.get_json(endpoints::circulating_supply(&self.base_url, chain)?)
+    }
+
+    /// Returns the total supply of the protocol's token.
+    pub fn get_total_supply(&self, chain: &str) -> Result<TotalSupplyResponse, OrcaError> {
+        self.get_json(endpoints::total_supply(&self.base_url, chain)?)
+    }
+
+    /// Returns a paginated list of tokens with optional filtering and sorting.
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_tokens(
+        &self,
+        chain: &str,
+        next: Option<&Cursor>,
+        previous: Option<&Cursor>,
+        size: Option<u32>,
+        sort_by: Option<&str>,
+        sort_direction: Option<&str>,
+        tokens: Option<&str>,
+    ) -> Result<Paginated<Token>, OrcaError> {
+        self.get_json(endpoints::tokens(
+            &self.base_url,
+            chain,
+            next,
+            previous,
+            size,
+            sort_by,
+            sort_direction,
+            tokens,
+        )?)
+    }
+
+    /// Returns a list of tokens that match the query string.
+    pub fn search_tokens(&self, chain: &str, query: &str) -> Result<Paginated<Token>, OrcaError> {
+        let params = SearchTokensParams {
+            q: query,
+            ..Default::default()
+        };
+        self.search_tokens_with_params(chain, params)
+    }
+
+    /// Returns a page of tokens matching `params.q`, with paging and sorting options.
+    pub fn search_tokens_with_params(
+        &self,
+        chain: &str,
+        params: SearchTokensParams<'_>,
+    ) -> Result<Paginated<Token>, OrcaError> {
+        self.get_json(endpoints::search_tokens(&self.base_url, chain, &params)?)
+    }
+
+    /// Returns detailed information for a specific token identified by its mint address.
+    pub fn get_token(
+        &self,
+        chain: &str,
+        mint_address: &str,
+    ) -> Result<Paginated<Token>, OrcaError> {
+        let page = self.get_json(endpoints::token(&self.base_url, chain, mint_address)?)?;
+        self.check_not_empty(page, "token", mint_address)
+    }
+
+    /// Returns the token identified by its mint address, unwrapping the first element of the
+    /// response.
+    ///
+    /// Returns `OrcaError::NotFound` carrying `mint_address` if the response contains no
+    /// token.
+    pub fn get_token_single(&self, chain: &str, mint_address: &str) -> Result<Token, OrcaError> {
+        self.get_token(chain, mint_address)?
+            .into_found("token", mint_address)
+    }
+
+    /// This endpoint returns the locked liquidity for a given whirlpool.
+    pub fn get_lock_info(&self, chain: &str, address: &str) -> Result<Vec<LockInfo>, OrcaError> {
+        self.get_json(endpoints::lock_info(&self.base_url, chain, address)?)
+    }
+
+    /// List whirlpools with optional filtering and pagination
+    pub fn get_pools(
+        &self,
+        chain: &str,
+        params: GetPoolsParams<'_>,
+    ) -> Result<Paginated<Whirlpool>, OrcaError> {
+        self.get_json(endpoints::pools(&self.base_url, chain, &params)?)
+    }
+
+    /// This endpoint allows searching for whirlpools
+    pub fn search_pools(
+        &self,
+        chain: &str,
+        params: SearchPoolsParams<'_>,
+    ) -> Result<Paginated<Whirlpool>, OrcaError> {
+        self.get_json(endpoints::search_pools(&self.base_url, chain, &params)?)
+    }
+
+    /// Get whirlpool data by address
+    pub fn get_pool(&self, chain: &str, address: &str) -> Result<Paginated<Whirlpool>, OrcaError> {
+        let page = self.get_json(endpoints::pool(&self.base_url, chain, address)?)?;
+        self.check_not_empty(page, "pool", address)
+    }
+
+    /// Get whirlpool data by address, unwrapping the first element of the response.
+    ///
+    /// Returns `OrcaError::NotFound` carrying `address` if the response contains no pool.
+    pub fn get_pool_single(&self, chain: &str, address: &str) -> Result<Whirlpool, OrcaError> {
+        self.get_pool(chain, address)?.into_found("pool", address)
+    }
+
+    /// Turns an empty page into `OrcaError::NotFound` when `empty_paginated_is_error` is set.
+    fn check_not_empty<T>(
+        &self,
+        page: Paginated<T>,
+        resource: &'static str,
+        id: &str,
+    ) -> Result<Paginated<T>, OrcaError> {
+        if self.empty_paginated_is_error && page.data.is_empty() {
+            return Err(OrcaError::NotFound {
+                resource,
+                id: id.to_string(),
+            });
+        }
+        Ok(page)
+    }
+
+    /// Sends a GET request and deserializes the JSON response body.
+    fn get_json<T: DeserializeOwned>(&self, url: Url) -> Result<T, OrcaError> {
+        let response = self.client.get(url).send()?;
+        let status = response.status();
+        if !status.is_success() {
+            let retry_after = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| parse_retry_after(value, SystemTime::now()));
+            let body = response.text().unwrap_or_default();
+            return Err(OrcaError::Api {
+                status: status.as_u16(),
+                body,
+                retry_after,
+            });
+        }
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let body = response.bytes()?;
+        check_json_content_type(content_type.as_deref(), &body)?;
+        Ok(serde_json::from_slice(&body)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::fixtures::{self, SOL_USDC_POOL, USDC_MINT};
+    use mockito::mock;
+
+    #[test]
+    fn test_get_pool_single() {
+        let _m = mock("GET", format!("/solana/pools/{}", SOL_USDC_POOL).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![fixtures::whirlpool_json()]))
+            .create();
+
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        let pool = client.get_pool_single("solana", SOL_USDC_POOL).unwrap();
+        assert_eq!(pool.address, SOL_USDC_POOL);
+    }
+
+    #[test]
+    fn test_get_token_single_not_found() {
+        let _m = mock("GET", format!("/eclipse/tokens/{}", USDC_MINT).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![]))
+            .create();
+
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        assert!(matches!(
+            client.get_token_single("eclipse", USDC_MINT),
+            Err(OrcaError::NotFound { resource: "token", id }) if id == USDC_MINT
+        ));
+        assert!(client
+            .get_token("eclipse", USDC_MINT)
+            .unwrap()
+            .data
+            .is_empty());
+
+        let strict = client.empty_paginated_is_error(true);
+        assert!(matches!(
+            strict.get_token("eclipse", USDC_MINT),
+            Err(OrcaError::NotFound { resource: "token", id }) if id == USDC_MINT
+        ));
+    }
+
+    #[test]
+    fn test_get_pools_shares_url_building() {
+        let _m = mock("GET", "/solana/pools?sortBy=tvl&size=5")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![]))
+            .create();
+
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        let params = GetPoolsParams {
+            sort_by: Some("tvl"),
+            size: Some(5),
+            ..Default::default()
+        };
+        assert!(client.get_pools("solana", params).unwrap().data.is_empty());
+
+        let invalid = GetPoolsParams {
+            size: Some(0),
+            ..Default::default()
+        };
+        assert!(matches!(
+            client.get_pools("solana", invalid),
+            Err(OrcaError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn test_api_error() {
+        let _m = mock("GET", "/solana/protocol")
+            .with_status(503)
+            .with_body("unavailable")
+            .create();
+
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        match client.get_protocol_info("solana") {
+            Err(OrcaError::Api { status, body, .. }) => {
+                assert_eq!(status, 503);
+                assert_eq!(body, "unavailable");
+            }
+            other => panic!("expected an API error, got {:?}", other),
+        }
+    }
+}
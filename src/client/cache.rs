@@ -0,0 +1,63 @@
+use crate::client::rt::Instant;
+use bytes::Bytes;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// An in-memory cache of response bodies keyed by request URL.
+pub(crate) struct ResponseCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, (Instant, Bytes)>>,
+}
+
+impl ResponseCache {
+    pub(crate) fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the body stored for `key` if it is younger than the TTL, evicting it otherwise.
+    pub(crate) fn get(&self, key: &str) -> Option<Bytes> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        match entries.get(key) {
+            Some((stored_at, body)) if stored_at.elapsed() < self.ttl => Some(body.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Stores `body` for `key`, first evicting every entry older than the TTL so keys that
+    /// are never read again do not pile up.
+    pub(crate) fn insert(&self, key: String, body: Bytes) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.retain(|_, (stored_at, _)| stored_at.elapsed() < self.ttl);
+        entries.insert(key, (Instant::now(), body));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_evicts_expired_entries() {
+        let cache = ResponseCache::new(Duration::ZERO);
+        for page in 0..10 {
+            cache.insert(
+                format!("/solana/pools?next={page}"),
+                Bytes::from_static(b"{}"),
+            );
+        }
+        assert_eq!(cache.entries.lock().unwrap().len(), 1);
+
+        let cache = ResponseCache::new(Duration::from_secs(60));
+        cache.insert("/solana/pools".to_string(), Bytes::from_static(b"{}"));
+        cache.insert("/eclipse/pools".to_string(), Bytes::from_static(b"{}"));
+        assert_eq!(cache.entries.lock().unwrap().len(), 2);
+    }
+}
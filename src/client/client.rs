@@ -1,25 +1,211 @@
+use crate::client::cache::ResponseCache;
+use crate::client::endpoints;
+use crate::client::observer::OrcaObserver;
+use crate::client::rate_limit::RateLimiter;
+#[cfg(feature = "record-replay")]
+use crate::client::replay;
+use crate::client::rt::{self, Instant, Interval};
+use crate::client::transport::{HttpTransport, ReqwestTransport, ResponseHeaders};
+use crate::error::error::OrcaError;
 use crate::models::models::{
-    CirculatingSupplyResponse, LockInfo, Paginated, ProtocolInfo, TimePeriod, Token, TokenInfo,
-    TotalSupplyResponse, Whirlpool,
+    parse_decimal, tvl_histogram, Chain, CirculatingSupplyResponse, Cursor, LockInfo, Paginated,
+    ProtocolInfo, SearchResults, TimePeriod, Token, TokenInfo, TokenSupply, TotalSupplyResponse,
+    Whirlpool,
 };
+use bytes::Bytes;
+use futures::future;
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
+#[cfg(not(target_arch = "wasm32"))]
+use reqwest::Proxy;
 use reqwest::{Client, Url};
-use std::error::Error;
+use rust_decimal::Decimal;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "record-replay")]
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use url::ParseError;
 
-const BASE_URL: &str = "https://api.orca.so/v2";
+pub(crate) const DEFAULT_HOST: &str = "https://api.orca.so";
+pub(crate) const DEFAULT_API_VERSION: &str = "v2";
+
+/// The largest page size the list endpoints accept.
+pub const MAX_PAGE_SIZE: u32 = 1000;
+
+/// Query parameters whose values are never written to logs.
+#[cfg(feature = "tracing")]
+const SENSITIVE_QUERY_PARAMS: &[&str] = &[
+    "access_token",
+    "api_key",
+    "apikey",
+    "auth",
+    "authorization",
+    "key",
+    "password",
+    "secret",
+    "signature",
+];
+
+/// The only field of a pool that `get_pool_addresses` deserializes; serde skips the rest.
+#[derive(Deserialize)]
+struct PoolAddress {
+    address: String,
+}
 
 /// The main client for interacting with the Orca Public API.
-pub struct OrcaClient {
-    client: Client,
+///
+/// Requests go through `reqwest` by default; see `transport::HttpTransport` to plug in
+/// another HTTP stack.
+#[derive(Clone)]
+pub struct OrcaClient<H = ReqwestTransport> {
+    transport: H,
     base_url: String,
+    /// `base_url` parsed, which request URLs are compared against.
+    base: Url,
+    fallback_base_urls: Arc<[Url]>,
+    retry_policy: RetryPolicy,
+    options: RequestOptions,
+    cache: Option<Arc<ResponseCache>>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    empty_paginated_is_error: bool,
+    bytes_received: Arc<AtomicU64>,
+    byte_quota: Option<u64>,
+    default_pool_params: GetPoolsParams<'static>,
+    observer: Option<Arc<dyn OrcaObserver>>,
+    #[cfg(feature = "record-replay")]
+    record_dir: Option<Arc<PathBuf>>,
+    #[cfg(any(feature = "tracing", feature = "debug"))]
+    slow_request_threshold: Option<Duration>,
+    #[cfg(feature = "tracing")]
+    redacted_params: Vec<String>,
+}
+
+/// Builder for an `OrcaClient` with non-default settings.
+pub struct OrcaClientBuilder {
+    host: String,
+    api_version: String,
+    base_url: Option<String>,
+    fallback_hosts: Vec<String>,
+    retry_policy: RetryPolicy,
+    cache_ttl: Option<Duration>,
+    requests_per_second: Option<u32>,
+    empty_paginated_is_error: bool,
+    byte_quota: Option<u64>,
+    default_pool_params: GetPoolsParams<'static>,
+    observer: Option<Arc<dyn OrcaObserver>>,
+    compression: bool,
+    #[cfg(not(target_arch = "wasm32"))]
+    proxies: Vec<Proxy>,
+    #[cfg(not(target_arch = "wasm32"))]
+    pool_max_idle_per_host: Option<usize>,
+    #[cfg(not(target_arch = "wasm32"))]
+    tcp_keepalive: Option<Duration>,
+    #[cfg(feature = "record-replay")]
+    record_dir: Option<PathBuf>,
+    #[cfg(any(feature = "tracing", feature = "debug"))]
+    slow_request_threshold: Option<Duration>,
+    #[cfg(feature = "tracing")]
+    redacted_params: Vec<String>,
+}
+
+/// Per-request options, applied with `OrcaClient::with_options`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RequestOptions {
+    /// Fail on the first error instead of going through the client's retry policy.
+    ///
+    /// Useful for latency-critical calls such as live quotes, where a stale answer after
+    /// several backoffs is worth less than an immediate error.
+    pub no_retry: bool,
+}
+
+/// How failed requests are retried, set with `OrcaClientBuilder::with_retry_policy`.
+///
+/// The wait before retry `n` (starting at 0) is `initial_backoff * multiplier^n`, capped at
+/// `max_backoff`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt; `0` disables retrying.
+    pub max_retries: u32,
+    /// The wait before the first retry.
+    pub initial_backoff: Duration,
+    /// The longest wait between two attempts. A `Retry-After` delay longer than this is not
+    /// waited out; the error is returned instead.
+    pub max_backoff: Duration,
+    /// The factor the wait grows by after each retry.
+    pub multiplier: f64,
+    /// Waits a random duration between half and all of the computed backoff, so clients that
+    /// failed together do not retry in lockstep.
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    /// Three retries starting at 200ms and doubling up to 10s, with jitter.
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(10),
+            multiplier: 2.0,
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Returns the wait before retry `attempt`, counting from 0.
+    ///
+    /// With `jitter` enabled the result is random, between half of and the full backoff.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = i32::try_from(attempt).unwrap_or(i32::MAX);
+        let secs = self.initial_backoff.as_secs_f64() * self.multiplier.powi(exponent);
+        let backoff = Duration::try_from_secs_f64(secs)
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff);
+        if self.jitter {
+            backoff.mul_f64(0.5 + 0.5 * random_fraction())
+        } else {
+            backoff
+        }
+    }
+}
+
+/// Returns a pseudo-random number in `[0, 1)`, good enough to spread out retries.
+fn random_fraction() -> f64 {
+    use std::hash::{BuildHasher, Hasher};
+    // Each `RandomState` is seeded with fresh keys, so hashing nothing yields a new value.
+    let hash = std::collections::hash_map::RandomState::new()
+        .build_hasher()
+        .finish();
+    (hash >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Metadata about a successful response, returned by the `*_with_meta` methods.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResponseMeta {
+    /// The HTTP status code.
+    pub status: u16,
+    /// The `x-ratelimit-limit` header: requests allowed per rate-limit window.
+    pub rate_limit_limit: Option<u64>,
+    /// The `x-ratelimit-remaining` header: requests left in the current window.
+    pub rate_limit_remaining: Option<u64>,
+    /// The `x-ratelimit-reset` header: when the current window resets, as sent by the API.
+    pub rate_limit_reset: Option<u64>,
+    /// Time from sending the request to receiving the whole body, including any wait for
+    /// the client's rate limiter. Only the final attempt is measured when retries occur.
+    pub latency: Duration,
 }
 
 /// Parameters for the `get_pools` endpoint.
-#[derive(Default)]
+#[derive(Debug, Default, Clone, Copy)]
 pub struct GetPoolsParams<'a> {
     pub sort_by: Option<&'a str>,
     pub sort_direction: Option<&'a str>,
-    pub next: Option<&'a str>,
-    pub previous: Option<&'a str>,
+    pub next: Option<&'a Cursor>,
+    pub previous: Option<&'a Cursor>,
     pub has_rewards: Option<bool>,
     pub has_warning: Option<bool>,
     pub has_adaptive_fee: Option<bool>,
@@ -28,18 +214,21 @@ pub struct GetPoolsParams<'a> {
     pub min_volume: Option<f64>,
     pub min_locked_liquidity_percent: Option<f64>,
     pub size: Option<u32>,
-    pub token: Option<&'a [u64]>,
+    /// Mint addresses; keeps pools that have any of them as token A or token B. Each mint is
+    /// sent as a separate `token` query parameter.
+    pub token: Option<&'a [&'a str]>,
+    /// Mint addresses; keeps pools that contain all of these tokens.
     pub tokens_both_of: Option<&'a [&'a str]>,
     pub addresses: Option<&'a [&'a str]>,
     pub stats: Option<&'a [TimePeriod]>,
     pub include_blocked: Option<bool>,
 }
 
-#[derive(Default)]
+#[derive(Default, Clone, Copy)]
 /// Parameters for the `search_pools` endpoint.
 pub struct SearchPoolsParams<'a> {
     pub q: &'a str,
-    pub next: Option<&'a str>,
+    pub next: Option<&'a Cursor>,
     pub size: Option<u32>,
     pub sort_by: Option<&'a str>,
     pub sort_direction: Option<&'a str>,
@@ -52,577 +241,4075 @@ pub struct SearchPoolsParams<'a> {
     pub has_locked_liquidity: Option<bool>,
 }
 
+/// Parameters for the `search_tokens` endpoint.
+#[derive(Default, Clone, Copy)]
+pub struct SearchTokensParams<'a> {
+    pub q: &'a str,
+    pub next: Option<&'a Cursor>,
+    pub size: Option<u32>,
+    pub sort_by: Option<&'a str>,
+    pub sort_direction: Option<&'a str>,
+}
+
+impl<'a> GetPoolsParams<'a> {
+    /// Fills every field left `None` with the value from `defaults`.
+    pub fn or_defaults(self, defaults: GetPoolsParams<'a>) -> GetPoolsParams<'a> {
+        GetPoolsParams {
+            sort_by: self.sort_by.or(defaults.sort_by),
+            sort_direction: self.sort_direction.or(defaults.sort_direction),
+            next: self.next.or(defaults.next),
+            previous: self.previous.or(defaults.previous),
+            has_rewards: self.has_rewards.or(defaults.has_rewards),
+            has_warning: self.has_warning.or(defaults.has_warning),
+            has_adaptive_fee: self.has_adaptive_fee.or(defaults.has_adaptive_fee),
+            is_wavebreak: self.is_wavebreak.or(defaults.is_wavebreak),
+            min_tvl: self.min_tvl.or(defaults.min_tvl),
+            min_volume: self.min_volume.or(defaults.min_volume),
+            min_locked_liquidity_percent: self
+                .min_locked_liquidity_percent
+                .or(defaults.min_locked_liquidity_percent),
+            size: self.size.or(defaults.size),
+            token: self.token.or(defaults.token),
+            tokens_both_of: self.tokens_both_of.or(defaults.tokens_both_of),
+            addresses: self.addresses.or(defaults.addresses),
+            stats: self.stats.or(defaults.stats),
+            include_blocked: self.include_blocked.or(defaults.include_blocked),
+        }
+    }
+
+    /// Checks for parameters the API would reject, so they fail before a request is sent.
+    pub fn validate(&self) -> Result<(), OrcaError> {
+        let invalid = |message: String| Err(OrcaError::Validation(message));
+        // A page holds at least one pool and at most `MAX_PAGE_SIZE`.
+        if let Some(size) = self.size {
+            if size == 0 || size > MAX_PAGE_SIZE {
+                return invalid(format!(
+                    "`size` must be between 1 and {}, got {}",
+                    MAX_PAGE_SIZE, size
+                ));
+            }
+        }
+        // Minimums are amounts in USDC and cannot be negative, NaN or infinite.
+        for (name, value) in [("min_tvl", self.min_tvl), ("min_volume", self.min_volume)] {
+            if let Some(value) = value {
+                if !value.is_finite() || value < 0.0 {
+                    return invalid(format!(
+                        "`{}` must be a non-negative number, got {}",
+                        name, value
+                    ));
+                }
+            }
+        }
+        // A percentage of the pool's liquidity.
+        if let Some(percent) = self.min_locked_liquidity_percent {
+            if !(0.0..=100.0).contains(&percent) {
+                return invalid(format!(
+                    "`min_locked_liquidity_percent` must be between 0 and 100, got {}",
+                    percent
+                ));
+            }
+        }
+        // A page is requested relative to one cursor only.
+        if self.next.is_some() && self.previous.is_some() {
+            return invalid("`next` and `previous` cannot both be set".to_string());
+        }
+        if let Some(direction) = self.sort_direction {
+            if direction != "asc" && direction != "desc" {
+                return invalid(format!(
+                    "`sort_direction` must be \"asc\" or \"desc\", got {:?}",
+                    direction
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for OrcaClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Default for OrcaClientBuilder {
+    fn default() -> Self {
+        Self {
+            host: DEFAULT_HOST.to_string(),
+            api_version: DEFAULT_API_VERSION.to_string(),
+            base_url: None,
+            fallback_hosts: Vec::new(),
+            retry_policy: RetryPolicy {
+                max_retries: 0,
+                ..Default::default()
+            },
+            cache_ttl: None,
+            requests_per_second: None,
+            empty_paginated_is_error: false,
+            byte_quota: None,
+            default_pool_params: GetPoolsParams::default(),
+            observer: None,
+            compression: true,
+            #[cfg(not(target_arch = "wasm32"))]
+            proxies: Vec::new(),
+            #[cfg(not(target_arch = "wasm32"))]
+            pool_max_idle_per_host: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            tcp_keepalive: None,
+            #[cfg(feature = "record-replay")]
+            record_dir: None,
+            #[cfg(any(feature = "tracing", feature = "debug"))]
+            slow_request_threshold: None,
+            #[cfg(feature = "tracing")]
+            redacted_params: Vec::new(),
+        }
+    }
+}
+
+impl OrcaClientBuilder {
+    /// Creates a builder for `https://api.orca.so/v2` with retries disabled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the full base URL requests are sent to, version path included. Trailing slashes
+    /// are ignored.
+    ///
+    /// Takes precedence over `with_host` and `with_api_version`. `build` returns
+    /// `OrcaError::InvalidUrl` if `base_url` is not a valid base URL.
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = Some(base_url.to_string());
+        self
+    }
+
+    /// Sets the scheme and host requests are sent to, e.g. `https://api.orca.so`.
+    ///
+    /// The base URL is the host followed by the API version path. The host may carry a path
+    /// prefix, such as `https://gateway.example.com/orca`, which is kept in every request.
+    pub fn with_host(mut self, host: &str) -> Self {
+        self.host = host.to_string();
+        self
+    }
+
+    /// Sets the API version path segment, `v2` by default.
+    pub fn with_api_version(mut self, api_version: &str) -> Self {
+        self.api_version = api_version.to_string();
+        self
+    }
+
+    /// Sets backup hosts a request fails over to, in order, when the primary base URL fails.
+    ///
+    /// Each host is combined with the API version path like `with_host`. Failover only
+    /// happens on connection errors, timeouts and `5xx` responses; any other error, a `4xx`
+    /// included, is returned straight away. If every host fails, the last host's error is
+    /// returned. With retries enabled, each retry walks the hosts again from the primary.
+    /// `build` returns `OrcaError::InvalidUrl` if a host is not a valid base URL.
+    pub fn with_fallback_hosts(mut self, hosts: Vec<String>) -> Self {
+        self.fallback_hosts = hosts;
+        self
+    }
+
+    /// Retries failed requests up to `max_retries` times.
+    ///
+    /// Connection errors, timeouts, `429` and `5xx` responses are retried, waiting
+    /// `base_delay * 2^attempt` between attempts, without jitter, or as long as the
+    /// response's `Retry-After` header asks. Use `with_retry_policy` to configure the backoff
+    /// curve.
+    pub fn with_retries(self, max_retries: u32, base_delay: Duration) -> Self {
+        self.with_retry_policy(RetryPolicy {
+            max_retries,
+            initial_backoff: base_delay,
+            max_backoff: Duration::MAX,
+            multiplier: 2.0,
+            jitter: false,
+        })
+    }
+
+    /// Retries failed requests according to `policy`.
+    ///
+    /// Connection errors, timeouts, `429` and `5xx` responses are retried. A response with a
+    /// valid `Retry-After` header, in seconds or as an HTTP-date, is retried after the delay
+    /// it asks for instead of the policy's backoff, unless the delay is longer than
+    /// `max_backoff`, in which case the error is returned without retrying.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Caches successful responses in memory for `ttl`.
+    ///
+    /// Entries are keyed by the full request URL, query string included, so a call with
+    /// different parameters never reads another call's entry. Expired entries are evicted
+    /// whenever a new response is stored. The cache belongs to the built client (and copies
+    /// made with `OrcaClient::with_options`); separate clients never share entries.
+    pub fn with_cache(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = Some(ttl);
+        self
+    }
+
+    /// Limits the client to `requests_per_second` outgoing requests.
+    ///
+    /// Every request, including retries and each page of a stream, waits for its slot before
+    /// it is sent. Cache hits are not counted. The limit is shared by copies made with
+    /// `OrcaClient::with_options`. `build` returns `OrcaError::Validation` if
+    /// `requests_per_second` is zero.
+    pub fn with_rate_limit(mut self, requests_per_second: u32) -> Self {
+        self.requests_per_second = Some(requests_per_second);
+        self
+    }
+
+    /// Routes requests through `proxy`. Can be called several times to add proxies for
+    /// different schemes or hosts.
+    ///
+    /// Without an explicit proxy the client uses the `HTTP_PROXY`, `HTTPS_PROXY`,
+    /// `ALL_PROXY` and `NO_PROXY` environment variables. Once a proxy is set here, those
+    /// variables are ignored and only the proxies given to this method are used.
+    ///
+    /// Not available on wasm32, where requests go through the browser's own proxy settings.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_proxy(mut self, proxy: Proxy) -> Self {
+        self.proxies.push(proxy);
+        self
+    }
+
+    /// Keeps at most `max` idle connections per host in the connection pool, instead of
+    /// `reqwest`'s default of no limit.
+    ///
+    /// Not available on wasm32, where the browser manages connections.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.pool_max_idle_per_host = Some(max);
+        self
+    }
+
+    /// Enables TCP keepalive probes on connections, sent every `interval`. Off by default,
+    /// as in `reqwest`.
+    ///
+    /// Not available on wasm32, where the browser manages connections.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_tcp_keepalive(mut self, interval: Duration) -> Self {
+        self.tcp_keepalive = Some(interval);
+        self
+    }
+
+    /// Makes `get_pool` and `get_token` return `OrcaError::NotFound` when the response has an
+    /// empty `data` array, instead of an empty page. Off by default.
+    pub fn empty_paginated_is_error(mut self, enabled: bool) -> Self {
+        self.empty_paginated_is_error = enabled;
+        self
+    }
+
+    /// Enables or disables gzip and brotli compressed responses. On by default.
+    ///
+    /// Compressed responses are decompressed transparently. In the tests, a page of 100
+    /// pools with gzip is about 13x smaller on the wire. `bytes_received` and
+    /// `with_byte_quota` count decompressed bytes, so they do not reflect the savings on the
+    /// wire.
+    pub fn with_compression(mut self, enabled: bool) -> Self {
+        self.compression = enabled;
+        self
+    }
+
+    /// Stops sending requests once `max_bytes` of response bodies have been received.
+    ///
+    /// The limit applies to the count reported by `OrcaClient::bytes_received`: decompressed
+    /// body bytes, not network egress. The request that crosses the limit still
+    /// completes; every later request fails with `OrcaError::QuotaExceeded` without being
+    /// sent. The count is shared by copies made with `OrcaClient::with_options`.
+    pub fn with_byte_quota(mut self, max_bytes: u64) -> Self {
+        self.byte_quota = Some(max_bytes);
+        self
+    }
+
+    /// Sets parameters applied to every `/pools` request, such as `get_pools` and the helpers
+    /// built on it.
+    ///
+    /// Defaults are merged field by field with the parameters of each call: a field the call
+    /// sets wins, and a field it leaves `None` takes the default. A default cannot be unset
+    /// for a single call, only overridden with another value.
+    ///
+    /// Cursors and address filters (`next`, `previous`, `token` and `addresses`) only make
+    /// sense for a single call, so `build` returns `OrcaError::Validation` if any is set.
+    /// A `ReplayClient` replaying the client's recordings needs the same defaults, set with
+    /// `ReplayClient::with_default_pool_params`.
+    ///
+    /// ```rust,no_run
+    /// # use api_orca_so_rs::client::client::{GetPoolsParams, OrcaClient};
+    /// let client = OrcaClient::builder()
+    ///     .with_default_pool_params(GetPoolsParams {
+    ///         include_blocked: Some(false),
+    ///         size: Some(100),
+    ///         ..Default::default()
+    ///     })
+    ///     .build()?;
+    /// # Ok::<(), api_orca_so_rs::error::error::OrcaError>(())
+    /// ```
+    pub fn with_default_pool_params(mut self, params: GetPoolsParams<'static>) -> Self {
+        self.default_pool_params = params;
+        self
+    }
+
+    /// Calls `observer` around every request the client sends, retries included.
+    ///
+    /// The observer is shared by copies made with `OrcaClient::with_options`. See
+    /// `observer::OrcaObserver`.
+    pub fn with_observer(mut self, observer: Arc<dyn OrcaObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Writes every successful response to `dir`, for replay with `replay::ReplayClient`.
+    ///
+    /// Cache hits and the `*_with_meta` methods are not recorded. A failure to write a
+    /// recording fails the request with `OrcaError::Io`.
+    #[cfg(feature = "record-replay")]
+    pub fn with_recording(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.record_dir = Some(dir.into());
+        self
+    }
+
+    /// Logs a warning, with the endpoint path and elapsed time, for every request that takes
+    /// longer than `threshold`. Disabled by default.
+    ///
+    /// Each attempt is timed separately, from sending the request to receiving the whole
+    /// body; time spent waiting for the rate limiter is not counted. Warnings go to `tracing`
+    /// with the `tracing` feature and to `log` with the `debug` feature.
+    #[cfg(any(feature = "tracing", feature = "debug"))]
+    pub fn with_slow_request_threshold(mut self, threshold: Duration) -> Self {
+        self.slow_request_threshold = Some(threshold);
+        self
+    }
+
+    /// Redacts the values of these query parameters, in addition to the built-in list of
+    /// credential-like names, when requests are logged.
+    #[cfg(feature = "tracing")]
+    pub fn with_redacted_query_params(mut self, params: &[&str]) -> Self {
+        self.redacted_params = params.iter().map(|p| p.to_ascii_lowercase()).collect();
+        self
+    }
+
+    /// Builds the client.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn build(mut self) -> Result<OrcaClient, OrcaError> {
+        let mut builder = Client::builder().tcp_keepalive(self.tcp_keepalive);
+        if let Some(max) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max);
+        }
+        let client = std::mem::take(&mut self.proxies)
+            .into_iter()
+            .fold(builder, |builder, proxy| builder.proxy(proxy))
+            .gzip(self.compression)
+            .brotli(self.compression)
+            .build()?;
+        self.build_with_transport(ReqwestTransport::new(client))
+    }
+
+    /// Builds the client on top of the browser's `fetch`, which negotiates compression
+    /// itself.
+    #[cfg(target_arch = "wasm32")]
+    pub fn build(self) -> Result<OrcaClient, OrcaError> {
+        let client = Client::builder().build()?;
+        self.build_with_transport(ReqwestTransport::new(client))
+    }
+
+    /// Builds a client that sends its requests through `transport` instead of `reqwest`.
+    ///
+    /// Options that configure the `reqwest` client (`with_compression`, `with_proxy`,
+    /// `with_pool_max_idle_per_host` and `with_tcp_keepalive`) have no effect here; configure
+    /// them on the transport instead.
+    pub fn build_with_transport<H: HttpTransport>(
+        self,
+        transport: H,
+    ) -> Result<OrcaClient<H>, OrcaError> {
+        let defaults = &self.default_pool_params;
+        let per_call = [
+            ("next", defaults.next.is_some()),
+            ("previous", defaults.previous.is_some()),
+            ("token", defaults.token.is_some()),
+            ("addresses", defaults.addresses.is_some()),
+        ];
+        if let Some((field, _)) = per_call.iter().find(|(_, set)| *set) {
+            return Err(OrcaError::Validation(format!(
+                "`{field}` cannot be a default pool parameter"
+            )));
+        }
+        if self.requests_per_second == Some(0) {
+            return Err(OrcaError::Validation(
+                "`requests_per_second` must be at least 1".to_string(),
+            ));
+        }
+        let base_url = match self.base_url {
+            Some(base_url) => base_url.trim_end_matches('/').to_string(),
+            None => host_base_url(&self.host, &self.api_version),
+        };
+        let base = parse_base_url(&base_url)?;
+        let fallback_base_urls = self
+            .fallback_hosts
+            .iter()
+            .map(|host| parse_base_url(&host_base_url(host, &self.api_version)))
+            .collect::<Result<_, OrcaError>>()?;
+        Ok(OrcaClient {
+            transport,
+            base_url,
+            base,
+            fallback_base_urls,
+            retry_policy: self.retry_policy,
+            options: RequestOptions::default(),
+            cache: self.cache_ttl.map(|ttl| Arc::new(ResponseCache::new(ttl))),
+            rate_limiter: self
+                .requests_per_second
+                .map(|rps| Arc::new(RateLimiter::new(rps))),
+            empty_paginated_is_error: self.empty_paginated_is_error,
+            bytes_received: Arc::new(AtomicU64::new(0)),
+            byte_quota: self.byte_quota,
+            default_pool_params: self.default_pool_params,
+            observer: self.observer,
+            #[cfg(feature = "record-replay")]
+            record_dir: self.record_dir.map(Arc::new),
+            #[cfg(any(feature = "tracing", feature = "debug"))]
+            slow_request_threshold: self.slow_request_threshold,
+            #[cfg(feature = "tracing")]
+            redacted_params: self.redacted_params,
+        })
+    }
+}
+
 impl OrcaClient {
     /// Creates a new `OrcaClient` with the default base URL.
     pub fn new() -> Self {
-        Self {
-            client: Client::new(),
-            base_url: BASE_URL.to_string(),
-        }
+        Self::builder()
+            .build()
+            .expect("failed to build the HTTP client")
     }
 
     /// Creates a new `OrcaClient` with a custom base URL.
+    ///
+    /// Panics if `base_url` is not a valid base URL; use the builder to handle that as an
+    /// error.
     pub fn with_base_url(base_url: &str) -> Self {
-        Self {
-            client: Client::new(),
-            base_url: base_url.to_string(),
+        Self::builder()
+            .with_base_url(base_url)
+            .build()
+            .expect("failed to build the HTTP client")
+    }
+
+    /// Returns a builder for configuring a new `OrcaClient`.
+    pub fn builder() -> OrcaClientBuilder {
+        OrcaClientBuilder::new()
+    }
+}
+
+impl<H: HttpTransport> OrcaClient<H> {
+    /// Returns the total size in bytes of the response bodies received by this client and its
+    /// copies, error responses included. Cache hits are not counted.
+    ///
+    /// This is not network egress. Bodies are counted after decompression, so with
+    /// compression on (the default) far fewer bytes cross the wire, and headers and TLS
+    /// framing are never counted. To track metered egress with this counter, turn compression
+    /// off with `OrcaClientBuilder::with_compression(false)`, so that the counted bodies are
+    /// what is actually transferred.
+    pub fn bytes_received(&self) -> u64 {
+        self.bytes_received.load(Ordering::Relaxed)
+    }
+
+    /// Returns a copy of this client that applies `options` to every request it sends.
+    ///
+    /// The copy shares the underlying connection pool with `self`.
+    pub fn with_options(&self, options: RequestOptions) -> OrcaClient<H> {
+        OrcaClient {
+            options,
+            ..self.clone()
         }
     }
 
     /// Returns general information about the Orca protocol.
-    pub async fn get_protocol_info(&self, chain: &str) -> Result<ProtocolInfo, Box<dyn Error>> {
-        let url = format!("{}/{}/protocol", self.base_url, chain);
-        let response = self.client.get(&url).send().await?;
-        let protocol_info = response.json::<ProtocolInfo>().await?;
-        Ok(protocol_info)
+    ///
+    /// ```rust,no_run
+    /// # use api_orca_so_rs::client::client::OrcaClient;
+    /// # async fn example() -> Result<(), api_orca_so_rs::error::error::OrcaError> {
+    /// let client = OrcaClient::new();
+    /// let info = client.get_protocol_info("solana").await?;
+    /// println!("TVL: {}", info.tvl);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_protocol_info(&self, chain: &str) -> Result<ProtocolInfo, OrcaError> {
+        let url = endpoints::protocol(&self.base_url, chain)?;
+        self.get_json(url).await
     }
 
     /// Returns detailed information about the Orca token.
-    pub async fn get_token_info(&self, chain: &str) -> Result<TokenInfo, Box<dyn Error>> {
-        let url = format!("{}/{}/protocol/token", self.base_url, chain);
-        let response = self.client.get(&url).send().await?;
-        let token_info = response.json::<TokenInfo>().await?;
-        Ok(token_info)
+    ///
+    /// ```rust,no_run
+    /// # use api_orca_so_rs::client::client::OrcaClient;
+    /// # async fn example() -> Result<(), api_orca_so_rs::error::error::OrcaError> {
+    /// let client = OrcaClient::new();
+    /// let info = client.get_token_info("solana").await?;
+    /// println!("circulating: {}", info.circulating_supply);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_token_info(&self, chain: &str) -> Result<TokenInfo, OrcaError> {
+        let url = endpoints::token_info(&self.base_url, chain)?;
+        self.get_json(url).await
     }
 
     /// Returns the circulating supply of the protocol's token.
+    ///
+    /// ```rust,no_run
+    /// # use api_orca_so_rs::client::client::OrcaClient;
+    /// # async fn example() -> Result<(), api_orca_so_rs::error::error::OrcaError> {
+    /// let client = OrcaClient::new();
+    /// let supply = client.get_circulating_supply("solana").await?;
+    /// println!("{}", supply.circulating_supply);
+    /// # Ok(())
+    /// # }
+    /// ```
     pub async fn get_circulating_supply(
         &self,
         chain: &str,
-    ) -> Result<CirculatingSupplyResponse, Box<dyn Error>> {
-        let url = format!("{}/{}/protocol/token/circulating_supply", self.base_url, chain);
-        let response = self.client.get(&url).send().await?;
-        let circulating_supply = response.json::<CirculatingSupplyResponse>().await?;
-        Ok(circulating_supply)
+    ) -> Result<CirculatingSupplyResponse, OrcaError> {
+        let url = endpoints::circulating_supply(&self.base_url, chain)?;
+        self.get_json(url).await
     }
 
     /// Returns the total supply of the protocol's token.
-    pub async fn get_total_supply(
-        &self,
-        chain: &str,
-    ) -> Result<TotalSupplyResponse, Box<dyn Error>> {
-        let url = format!("{}/{}/protocol/token/total_supply", self.base_url, chain);
-        let response = self.client.get(&url).send().await?;
-        let total_supply = response.json::<TotalSupplyResponse>().await?;
-        Ok(total_supply)
+    ///
+    /// ```rust,no_run
+    /// # use api_orca_so_rs::client::client::OrcaClient;
+    /// # async fn example() -> Result<(), api_orca_so_rs::error::error::OrcaError> {
+    /// let client = OrcaClient::new();
+    /// let supply = client.get_total_supply("solana").await?;
+    /// println!("{}", supply.total_supply);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_total_supply(&self, chain: &str) -> Result<TotalSupplyResponse, OrcaError> {
+        let url = endpoints::total_supply(&self.base_url, chain)?;
+        self.get_json(url).await
+    }
+
+    /// Returns both the circulating and the total supply of the protocol's token, fetched
+    /// concurrently and parsed as decimals.
+    pub async fn get_token_supply(&self, chain: &str) -> Result<TokenSupply, OrcaError> {
+        let (circulating, total) = future::try_join(
+            self.get_circulating_supply(chain),
+            self.get_total_supply(chain),
+        )
+        .await?;
+        Ok(TokenSupply {
+            circulating: parse_decimal("circulating_supply", &circulating.circulating_supply)?,
+            total: parse_decimal("total_supply", &total.total_supply)?,
+        })
     }
 
     /// Returns a paginated list of tokens with optional filtering and sorting.
+    ///
+    /// ```rust,no_run
+    /// # use api_orca_so_rs::client::client::OrcaClient;
+    /// # async fn example() -> Result<(), api_orca_so_rs::error::error::OrcaError> {
+    /// let client = OrcaClient::new();
+    /// let page = client
+    ///     .get_tokens("solana", None, None, Some(50), None, None, None)
+    ///     .await?;
+    /// for token in &page {
+    ///     println!("{} {}", token.address, token.price_usdc);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[allow(clippy::too_many_arguments)]
     pub async fn get_tokens<'a>(
         &self,
         chain: &str,
-        next: Option<&'a str>,
-        previous: Option<&'a str>,
+        next: Option<&'a Cursor>,
+        previous: Option<&'a Cursor>,
         size: Option<u32>,
         sort_by: Option<&'a str>,
         sort_direction: Option<&'a str>,
         tokens: Option<&'a str>,
-    ) -> Result<Paginated<Token>, Box<dyn Error>> {
-        let mut url = Url::parse(&format!("{}/{}/tokens", self.base_url, chain))?;
-
-        if let Some(next) = next {
-            url.query_pairs_mut().append_pair("next", next);
-        }
-        if let Some(previous) = previous {
-            url.query_pairs_mut().append_pair("previous", previous);
-        }
-        if let Some(size) = size {
-            url.query_pairs_mut()
-                .append_pair("size", &size.to_string());
-        }
-        if let Some(sort_by) = sort_by {
-            url.query_pairs_mut().append_pair("sort_by", sort_by);
-        }
-        if let Some(sort_direction) = sort_direction {
-            url.query_pairs_mut()
-                .append_pair("sort_direction", sort_direction);
-        }
-        if let Some(tokens) = tokens {
-            url.query_pairs_mut().append_pair("tokens", tokens);
-        }
-
-        let response = self.client.get(url).send().await?;
-        let tokens = response.json::<Paginated<Token>>().await?;
-        Ok(tokens)
+    ) -> Result<Paginated<Token>, OrcaError> {
+        let url = endpoints::tokens(
+            &self.base_url,
+            chain,
+            next,
+            previous,
+            size,
+            sort_by,
+            sort_direction,
+            tokens,
+        )?;
+        self.get_json(url).await
     }
 
     /// Returns a list of tokens that match the query string.
+    ///
+    /// ```rust,no_run
+    /// # use api_orca_so_rs::client::client::OrcaClient;
+    /// # async fn example() -> Result<(), api_orca_so_rs::error::error::OrcaError> {
+    /// let client = OrcaClient::new();
+    /// let page = client.search_tokens("solana", "orca").await?;
+    /// println!("{} matches", page.data.len());
+    /// # Ok(())
+    /// # }
+    /// ```
     pub async fn search_tokens(
         &self,
         chain: &str,
         query: &str,
-    ) -> Result<Paginated<Token>, Box<dyn Error>> {
-        let mut url = Url::parse(&format!("{}/{}/tokens/search", self.base_url, chain))?;
-        url.query_pairs_mut().append_pair("q", query);
+    ) -> Result<Paginated<Token>, OrcaError> {
+        let params = SearchTokensParams {
+            q: query,
+            ..Default::default()
+        };
+        self.search_tokens_with_params(chain, params).await
+    }
+
+    /// Returns a page of tokens matching `params.q`, with paging and sorting options.
+    ///
+    /// ```rust,no_run
+    /// # use api_orca_so_rs::client::client::{OrcaClient, SearchTokensParams};
+    /// # async fn example() -> Result<(), api_orca_so_rs::error::error::OrcaError> {
+    /// let client = OrcaClient::new();
+    /// let params = SearchTokensParams {
+    ///     q: "usd",
+    ///     size: Some(10),
+    ///     sort_by: Some("volume"),
+    ///     sort_direction: Some("desc"),
+    ///     ..Default::default()
+    /// };
+    /// let page = client.search_tokens_with_params("solana", params).await?;
+    /// println!("{} matches", page.data.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn search_tokens_with_params<'a>(
+        &self,
+        chain: &str,
+        params: SearchTokensParams<'a>,
+    ) -> Result<Paginated<Token>, OrcaError> {
+        let url = endpoints::search_tokens(&self.base_url, chain, &params)?;
+        self.get_json(url).await
+    }
 
-        let response = self.client.get(url).send().await?;
-        let tokens = response.json::<Paginated<Token>>().await?;
-        Ok(tokens)
+    /// Searches tokens and pools for `query` at once, returning up to `limit` of each.
+    ///
+    /// `search_tokens_with_params` and `search_pools` are sent concurrently with a page size
+    /// of `limit`, and the call fails if either does. `limit` must be between 1 and
+    /// `MAX_PAGE_SIZE`.
+    ///
+    /// ```rust,no_run
+    /// # use api_orca_so_rs::client::client::OrcaClient;
+    /// # async fn example() -> Result<(), api_orca_so_rs::error::error::OrcaError> {
+    /// let client = OrcaClient::new();
+    /// let results = client.search("solana", "orca", 5).await?;
+    /// for token in &results.tokens {
+    ///     println!("token {}", token);
+    /// }
+    /// for pool in &results.pools {
+    ///     println!("pool {}", pool);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn search(
+        &self,
+        chain: &str,
+        query: &str,
+        limit: u32,
+    ) -> Result<SearchResults, OrcaError> {
+        if limit == 0 || limit > MAX_PAGE_SIZE {
+            return Err(OrcaError::Validation(format!(
+                "`limit` must be between 1 and {}, got {}",
+                MAX_PAGE_SIZE, limit
+            )));
+        }
+        let token_params = SearchTokensParams {
+            q: query,
+            size: Some(limit),
+            ..Default::default()
+        };
+        let pool_params = SearchPoolsParams {
+            q: query,
+            size: Some(limit),
+            ..Default::default()
+        };
+        let (tokens, pools) = future::try_join(
+            self.search_tokens_with_params(chain, token_params),
+            self.search_pools(chain, pool_params),
+        )
+        .await?;
+        let mut results = SearchResults {
+            tokens: tokens.data,
+            pools: pools.data,
+        };
+        results.tokens.truncate(limit as usize);
+        results.pools.truncate(limit as usize);
+        Ok(results)
     }
 
     /// Returns detailed information for a specific token identified by its mint address.
+    ///
+    /// If the client was built with `empty_paginated_is_error`, an empty response is returned
+    /// as `OrcaError::NotFound`.
+    ///
+    /// ```rust,no_run
+    /// # use api_orca_so_rs::client::client::OrcaClient;
+    /// # async fn example() -> Result<(), api_orca_so_rs::error::error::OrcaError> {
+    /// let client = OrcaClient::new();
+    /// let page = client
+    ///     .get_token("solana", "orcaEKTdK7LKz57vaAYr9QeNsVEPfiu6QeMU1kektZE")
+    ///     .await?;
+    /// println!("{:?}", page.data.first());
+    /// # Ok(())
+    /// # }
+    /// ```
     pub async fn get_token(
         &self,
         chain: &str,
         mint_address: &str,
-    ) -> Result<Paginated<Token>, Box<dyn Error>> {
-        let url = format!("{}/{}/tokens/{}", self.base_url, chain, mint_address);
-        let response = self.client.get(&url).send().await?;
-        let token = response.json::<Paginated<Token>>().await?;
-        Ok(token)
+    ) -> Result<Paginated<Token>, OrcaError> {
+        let url = endpoints::token(&self.base_url, chain, mint_address)?;
+        let page = self.get_json(url).await?;
+        self.check_not_empty(page, "token", mint_address)
     }
 
     /// This endpoint returns the locked liquidity for a given whirlpool.
+    ///
+    /// ```rust,no_run
+    /// # use api_orca_so_rs::client::client::OrcaClient;
+    /// # async fn example() -> Result<(), api_orca_so_rs::error::error::OrcaError> {
+    /// let client = OrcaClient::new();
+    /// let locks = client
+    ///     .get_lock_info("solana", "Czfq3xZZDmsdGdUyrNLtRhGc47cXcZtLG4crryfu44zE")
+    ///     .await?;
+    /// for lock in &locks {
+    ///     println!("{}: {}", lock.name, lock.locked_percentage);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
     pub async fn get_lock_info(
         &self,
         chain: &str,
         address: &str,
-    ) -> Result<Vec<LockInfo>, Box<dyn Error>> {
-        let url = format!("{}/{}/lock/{}", self.base_url, chain, address);
-        let response = self.client.get(&url).send().await?;
-        let lock_info = response.json::<Vec<LockInfo>>().await?;
-        Ok(lock_info)
+    ) -> Result<Vec<LockInfo>, OrcaError> {
+        let url = endpoints::lock_info(&self.base_url, chain, address)?;
+        self.get_json(url).await
+    }
+
+    /// Returns the sum of `locked_percentage` over every lock reported for a whirlpool.
+    ///
+    /// A whirlpool without locks has a total of zero. A malformed percentage fails the whole
+    /// call with `OrcaError::InvalidNumber`.
+    ///
+    /// ```rust,no_run
+    /// # use api_orca_so_rs::client::client::OrcaClient;
+    /// # async fn example() -> Result<(), api_orca_so_rs::error::error::OrcaError> {
+    /// let client = OrcaClient::new();
+    /// let locked = client
+    ///     .get_total_locked_percent("solana", "Czfq3xZZDmsdGdUyrNLtRhGc47cXcZtLG4crryfu44zE")
+    ///     .await?;
+    /// println!("{}% locked", locked);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_total_locked_percent(
+        &self,
+        chain: &str,
+        address: &str,
+    ) -> Result<Decimal, OrcaError> {
+        self.get_lock_info(chain, address)
+            .await?
+            .iter()
+            .map(LockInfo::percentage_decimal)
+            .sum()
     }
 
     /// List whirlpools with optional filtering and pagination
+    ///
+    /// ```rust,no_run
+    /// # use api_orca_so_rs::client::client::{GetPoolsParams, OrcaClient};
+    /// # async fn example() -> Result<(), api_orca_so_rs::error::error::OrcaError> {
+    /// let client = OrcaClient::new();
+    /// use api_orca_so_rs::models::models::TimePeriod;
+    ///
+    /// let stats = [TimePeriod::H24];
+    /// let params = GetPoolsParams {
+    ///     sort_by: Some("tvl"),
+    ///     sort_direction: Some("desc"),
+    ///     min_tvl: Some(100_000.0),
+    ///     has_warning: Some(false),
+    ///     stats: Some(&stats),
+    ///     size: Some(20),
+    ///     ..Default::default()
+    /// };
+    /// let page = client.get_pools("solana", params).await?;
+    /// for pool in &page {
+    ///     println!("{} TVL {}", pool.address, pool.tvl_usdc);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
     pub async fn get_pools<'a>(
         &self,
         chain: &str,
         params: GetPoolsParams<'a>,
-    ) -> Result<Paginated<Whirlpool>, Box<dyn Error>> {
-        let mut url = Url::parse(&format!("{}/{}/pools", self.base_url, chain))?;
-        let mut query_pairs = url.query_pairs_mut();
-
-        if let Some(sort_by) = params.sort_by {
-            query_pairs.append_pair("sortBy", sort_by);
-        }
-        if let Some(sort_direction) = params.sort_direction {
-            query_pairs.append_pair("sortDirection", sort_direction);
-        }
-        if let Some(next) = params.next {
-            query_pairs.append_pair("next", next);
-        }
-        if let Some(previous) = params.previous {
-            query_pairs.append_pair("previous", previous);
-        }
-        if let Some(has_rewards) = params.has_rewards {
-            query_pairs.append_pair("hasRewards", &has_rewards.to_string());
-        }
-        if let Some(has_warning) = params.has_warning {
-            query_pairs.append_pair("hasWarning", &has_warning.to_string());
-        }
-        if let Some(has_adaptive_fee) = params.has_adaptive_fee {
-            query_pairs.append_pair("hasAdaptiveFee", &has_adaptive_fee.to_string());
-        }
-        if let Some(is_wavebreak) = params.is_wavebreak {
-            query_pairs.append_pair("isWavebreak", &is_wavebreak.to_string());
-        }
-        if let Some(min_tvl) = params.min_tvl {
-            query_pairs.append_pair("minTvl", &min_tvl.to_string());
-        }
-        if let Some(min_volume) = params.min_volume {
-            query_pairs.append_pair("minVolume", &min_volume.to_string());
-        }
-        if let Some(min_locked_liquidity_percent) = params.min_locked_liquidity_percent {
-            query_pairs.append_pair(
-                "minLockedLiquidityPercent",
-                &min_locked_liquidity_percent.to_string(),
-            );
-        }
-        if let Some(size) = params.size {
-            query_pairs.append_pair("size", &size.to_string());
-        }
-        if let Some(token) = params.token {
-            for t in token {
-                query_pairs.append_pair("token", &t.to_string());
-            }
-        }
-        if let Some(tokens_both_of) = params.tokens_both_of {
-            for t in tokens_both_of {
-                query_pairs.append_pair("tokensBothOf", t);
-            }
-        }
-        if let Some(addresses) = params.addresses {
-            for a in addresses {
-                query_pairs.append_pair("addresses", a);
-            }
-        }
-        if let Some(stats) = params.stats {
-            for s in stats {
-                query_pairs.append_pair(
-                    "stats",
-                    &serde_json::to_string(s)
-                        .unwrap_or_default()
-                        .replace('"', ""),
-                );
-            }
-        }
-        if let Some(include_blocked) = params.include_blocked {
-            query_pairs.append_pair("includeBlocked", &include_blocked.to_string());
-        }
+    ) -> Result<Paginated<Whirlpool>, OrcaError> {
+        let url = self.pools_url(chain, params)?;
+        self.get_json(url).await
+    }
 
-        drop(query_pairs);
+    /// Like `get_pools`, also returning the status, rate-limit headers and latency of the
+    /// response.
+    ///
+    /// The request always goes to the network: the response cache is neither read nor
+    /// written, since a cached body has no headers or latency to report.
+    ///
+    /// ```rust,no_run
+    /// # use api_orca_so_rs::client::client::{GetPoolsParams, OrcaClient};
+    /// # async fn example() -> Result<(), api_orca_so_rs::error::error::OrcaError> {
+    /// let client = OrcaClient::new();
+    /// let (page, meta) = client
+    ///     .get_pools_with_meta("solana", GetPoolsParams::default())
+    ///     .await?;
+    /// println!("{} pools in {:?}", page.data.len(), meta.latency);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_pools_with_meta<'a>(
+        &self,
+        chain: &str,
+        params: GetPoolsParams<'a>,
+    ) -> Result<(Paginated<Whirlpool>, ResponseMeta), OrcaError> {
+        let url = self.pools_url(chain, params)?;
+        let (body, meta) = self.fetch_with_meta(url).await?;
+        Ok((serde_json::from_slice(&body)?, meta))
+    }
 
-        let response = self.client.get(url).send().await?;
-        let pools = response.json::<Paginated<Whirlpool>>().await?;
-        Ok(pools)
+    /// Like `get_pools`, keeping only pools that are not `Whirlpool::is_risky`.
+    ///
+    /// `has_warning=false` is sent to the API, then pools where either token lacks the
+    /// `verified` tag are dropped client-side. A returned page can therefore hold fewer than
+    /// `size` pools even when more exist; its `meta` cursors still page through the
+    /// unfiltered results.
+    ///
+    /// ```rust,no_run
+    /// # use api_orca_so_rs::client::client::{GetPoolsParams, OrcaClient};
+    /// # async fn example() -> Result<(), api_orca_so_rs::error::error::OrcaError> {
+    /// let client = OrcaClient::new();
+    /// let params = GetPoolsParams {
+    ///     min_tvl: Some(10_000.0),
+    ///     ..Default::default()
+    /// };
+    /// let page = client.get_safe_pools("solana", params).await?;
+    /// println!("{} safe pools", page.data.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_safe_pools<'a>(
+        &self,
+        chain: &str,
+        params: GetPoolsParams<'a>,
+    ) -> Result<Paginated<Whirlpool>, OrcaError> {
+        let params = GetPoolsParams {
+            has_warning: Some(false),
+            ..params
+        };
+        let mut page = self.get_pools(chain, params).await?;
+        page.data.retain(|pool| !pool.is_risky());
+        Ok(page)
     }
 
     /// This endpoint allows searching for whirlpools
+    ///
+    /// ```rust,no_run
+    /// # use api_orca_so_rs::client::client::{OrcaClient, SearchPoolsParams};
+    /// # async fn example() -> Result<(), api_orca_so_rs::error::error::OrcaError> {
+    /// let client = OrcaClient::new();
+    /// let params = SearchPoolsParams {
+    ///     q: "SOL-USDC",
+    ///     min_tvl: Some(50_000.0),
+    ///     verified_only: Some(true),
+    ///     size: Some(10),
+    ///     ..Default::default()
+    /// };
+    /// let page = client.search_pools("solana", params).await?;
+    /// println!("{} matches", page.data.len());
+    /// # Ok(())
+    /// # }
+    /// ```
     pub async fn search_pools<'a>(
         &self,
         chain: &str,
         params: SearchPoolsParams<'a>,
-    ) -> Result<Paginated<Whirlpool>, Box<dyn Error>> {
-        let mut url = Url::parse(&format!("{}/{}/pools/search", self.base_url, chain))?;
-        let mut query_pairs = url.query_pairs_mut();
-
-        query_pairs.append_pair("q", params.q);
-
-        if let Some(next) = params.next {
-            query_pairs.append_pair("next", next);
-        }
-        if let Some(size) = params.size {
-            query_pairs.append_pair("size", &size.to_string());
-        }
-        if let Some(sort_by) = params.sort_by {
-            query_pairs.append_pair("sortBy", sort_by);
-        }
-        if let Some(sort_direction) = params.sort_direction {
-            query_pairs.append_pair("sortDirection", sort_direction);
-        }
-        if let Some(min_tvl) = params.min_tvl {
-            query_pairs.append_pair("minTvl", &min_tvl.to_string());
-        }
-        if let Some(min_volume) = params.min_volume {
-            query_pairs.append_pair("minVolume", &min_volume.to_string());
-        }
-        if let Some(stats) = params.stats {
-            for s in stats {
-                query_pairs.append_pair(
-                    "stats",
-                    &serde_json::to_string(s)
-                        .unwrap_or_default()
-                        .replace('"', ""),
-                );
-            }
-        }
-        if let Some(user_tokens) = params.user_tokens {
-            for t in user_tokens {
-                query_pairs.append_pair("userTokens", t);
-            }
-        }
-        if let Some(has_rewards) = params.has_rewards {
-            query_pairs.append_pair("hasRewards", &has_rewards.to_string());
-        }
-        if let Some(verified_only) = params.verified_only {
-            query_pairs.append_pair("verifiedOnly", &verified_only.to_string());
-        }
-        if let Some(has_locked_liquidity) = params.has_locked_liquidity {
-            query_pairs.append_pair("hasLockedLiquidity", &has_locked_liquidity.to_string());
-        }
-
-        drop(query_pairs);
-        let response = self.client.get(url).send().await?;
-        let pools = response.json::<Paginated<Whirlpool>>().await?;
-        Ok(pools)
+    ) -> Result<Paginated<Whirlpool>, OrcaError> {
+        let url = endpoints::search_pools(&self.base_url, chain, &params)?;
+        self.get_json(url).await
     }
 
     /// Get whirlpool data by address
+    ///
+    /// If the client was built with `empty_paginated_is_error`, an empty response is returned
+    /// as `OrcaError::NotFound`.
+    ///
+    /// ```rust,no_run
+    /// # use api_orca_so_rs::client::client::OrcaClient;
+    /// # async fn example() -> Result<(), api_orca_so_rs::error::error::OrcaError> {
+    /// let client = OrcaClient::new();
+    /// let page = client
+    ///     .get_pool("solana", "Czfq3xZZDmsdGdUyrNLtRhGc47cXcZtLG4crryfu44zE")
+    ///     .await?;
+    /// println!("{:?}", page.data.first().map(|pool| &pool.price));
+    /// # Ok(())
+    /// # }
+    /// ```
     pub async fn get_pool(
         &self,
         chain: &str,
         address: &str,
-    ) -> Result<Paginated<Whirlpool>, Box<dyn Error>> {
-        let url = format!("{}/{}/pools/{}", self.base_url, chain, address);
-        let response = self.client.get(&url).send().await?;
-        let pool = response.json::<Paginated<Whirlpool>>().await?;
-        Ok(pool)
+    ) -> Result<Paginated<Whirlpool>, OrcaError> {
+        let url = endpoints::pool(&self.base_url, chain, address)?;
+        let page = self.get_json(url).await?;
+        self.check_not_empty(page, "pool", address)
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use mockito::mock;
 
-    #[tokio::test]
-    async fn test_get_protocol_info() {
-        let _m = mock("GET", "/solana/protocol")
-            .with_status(200)
-            .with_header("content-type", "application/json")
-            .with_body(
-                r#"{
-                    "fees24hUsdc": "317428.0521046",
-                    "revenue24hUsdc": "41265.646773",
-                    "tvl": "230551269.0085",
-                    "volume24hUsdc": "552567794.7830"
-                }"#,
-            )
-            .create();
+    /// Get whirlpool data by address, unwrapping the first element of the response.
+    ///
+    /// Returns `OrcaError::NotFound` carrying `address` if the response contains no pool.
+    ///
+    /// ```rust,no_run
+    /// # use api_orca_so_rs::client::client::OrcaClient;
+    /// # async fn example() -> Result<(), api_orca_so_rs::error::error::OrcaError> {
+    /// let client = OrcaClient::new();
+    /// let pool = client
+    ///     .get_pool_single("solana", "Czfq3xZZDmsdGdUyrNLtRhGc47cXcZtLG4crryfu44zE")
+    ///     .await?;
+    /// println!("{}", pool);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_pool_single(
+        &self,
+        chain: &str,
+        address: &str,
+    ) -> Result<Whirlpool, OrcaError> {
+        self.get_pool(chain, address)
+            .await?
+            .into_found("pool", address)
+    }
 
-        let client = OrcaClient::with_base_url(&mockito::server_url());
-        let result = client.get_protocol_info("solana").await;
+    /// Returns the token identified by its mint address, unwrapping the first element of the
+    /// response.
+    ///
+    /// Returns `OrcaError::NotFound` carrying `mint_address` if the response contains no
+    /// token.
+    ///
+    /// ```rust,no_run
+    /// # use api_orca_so_rs::client::client::OrcaClient;
+    /// # async fn example() -> Result<(), api_orca_so_rs::error::error::OrcaError> {
+    /// let client = OrcaClient::new();
+    /// let token = client
+    ///     .get_token_single("solana", "orcaEKTdK7LKz57vaAYr9QeNsVEPfiu6QeMU1kektZE")
+    ///     .await?;
+    /// println!("{}", token);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_token_single(
+        &self,
+        chain: &str,
+        mint_address: &str,
+    ) -> Result<Token, OrcaError> {
+        self.get_token(chain, mint_address)
+            .await?
+            .into_found("token", mint_address)
+    }
 
-        assert!(result.is_ok());
-        let protocol_info = result.unwrap();
-        assert_eq!(protocol_info.fees_24h_usdc, "317428.0521046");
+    /// Returns the USDC price of the token identified by `mint_address`.
+    ///
+    /// Returns `OrcaError::NotFound` if the API has no such token, whether it answers with an
+    /// empty page or a `404`.
+    ///
+    /// ```rust,no_run
+    /// # use api_orca_so_rs::client::client::OrcaClient;
+    /// # async fn example() -> Result<(), api_orca_so_rs::error::error::OrcaError> {
+    /// let client = OrcaClient::new();
+    /// let price = client
+    ///     .get_token_price("solana", "orcaEKTdK7LKz57vaAYr9QeNsVEPfiu6QeMU1kektZE")
+    ///     .await?;
+    /// println!("ORCA = ${}", price);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_token_price(
+        &self,
+        chain: &str,
+        mint_address: &str,
+    ) -> Result<Decimal, OrcaError> {
+        let token = match self.get_token_single(chain, mint_address).await {
+            Err(OrcaError::Api { status: 404, .. }) => {
+                return Err(OrcaError::NotFound {
+                    resource: "token",
+                    id: mint_address.to_string(),
+                })
+            }
+            result => result?,
+        };
+        parse_decimal("price_usdc", &token.price_usdc)
     }
 
-    #[tokio::test]
-    async fn test_get_token_info() {
-        let _m = mock("GET", "/solana/protocol/token")
-            .with_status(200)
-            .with_header("content-type", "application/json")
-            .with_body(
-                r#"{
-                    "circulatingSupply": "53275182.419413",
-                    "description": "Orca Token",
-                    "imageUrl": "https://raw.githubusercontent.com/solana-labs/token-list/main/assets/mainnet/orcaEKTdK7LKz57vaAYr9QeNsVEPfiu6QeMU1kektZE/logo.png",
-                    "name": "Orca",
-                    "price": "1.6767140",
-                    "stats": {
-                        "24h": {
-                            "volume": "594947.6898176792"
-                        }
+    /// Streams every pool matching `params`, following the `next` cursor until the last page.
+    ///
+    /// Pages are fetched lazily as the stream is polled. A failed page request is yielded as
+    /// an error and ends the stream.
+    ///
+    /// The stream is cancel-safe: the next page is only requested once every item of the
+    /// current page has been consumed, and dropping the stream while a page is loading drops
+    /// the in-flight request (and any pending retry) along with it.
+    ///
+    /// ```rust,no_run
+    /// # use api_orca_so_rs::client::client::{GetPoolsParams, OrcaClient};
+    /// # async fn example() -> Result<(), api_orca_so_rs::error::error::OrcaError> {
+    /// let client = OrcaClient::new();
+    /// use futures::TryStreamExt;
+    ///
+    /// let params = GetPoolsParams {
+    ///     size: Some(100),
+    ///     ..Default::default()
+    /// };
+    /// let mut pools = Box::pin(client.pools_stream("solana", params));
+    /// while let Some(pool) = pools.try_next().await? {
+    ///     println!("{}", pool.address);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn pools_stream<'a>(
+        &'a self,
+        chain: &'a str,
+        params: GetPoolsParams<'a>,
+    ) -> impl Stream<Item = Result<Whirlpool, OrcaError>> + 'a {
+        stream::try_unfold(Some(params.next.cloned()), move |cursor| async move {
+            let Some(cursor) = cursor else {
+                return Ok::<_, OrcaError>(None);
+            };
+            let page = self
+                .get_pools(
+                    chain,
+                    GetPoolsParams {
+                        next: cursor.as_ref(),
+                        ..params
                     },
-                    "symbol": "ORCA",
-                    "totalSupply": "99999712.243267"
+                )
+                .await?;
+            let next = match page.meta.next {
+                Some(next) if !page.data.is_empty() => Some(Some(next)),
+                _ => None,
+            };
+            Ok(Some((
+                stream::iter(page.data.into_iter().map(Ok::<_, OrcaError>)),
+                next,
+            )))
+        })
+        .try_flatten()
+    }
+
+    /// Like `pools_stream`, ending as soon as `cancel` fires.
+    ///
+    /// Cancellation ends the stream without an error. A page request in flight at that point
+    /// is dropped right away, closing its connection, rather than when the stream is dropped.
+    pub fn pools_stream_with_cancel<'a>(
+        &'a self,
+        chain: &'a str,
+        params: GetPoolsParams<'a>,
+        cancel: CancellationToken,
+    ) -> impl Stream<Item = Result<Whirlpool, OrcaError>> + 'a {
+        until_cancelled(self.pools_stream(chain, params), cancel)
+    }
+
+    /// Streams every pool matching `params` that passes `Whirlpool::validate`.
+    ///
+    /// Invalid pools are skipped; with the `debug` feature enabled each rejection is logged
+    /// at debug level. Errors fetching a page are still yielded.
+    pub fn valid_pools_stream<'a>(
+        &'a self,
+        chain: &'a str,
+        params: GetPoolsParams<'a>,
+    ) -> impl Stream<Item = Result<Whirlpool, OrcaError>> + 'a {
+        self.pools_stream(chain, params).filter(|pool| {
+            let keep = match pool {
+                Ok(pool) => match pool.validate() {
+                    Ok(()) => true,
+                    Err(_err) => {
+                        #[cfg(feature = "debug")]
+                        log::debug!("skipping invalid pool {}: {}", pool.address, _err);
+                        false
+                    }
+                },
+                Err(_) => true,
+            };
+            std::future::ready(keep)
+        })
+    }
+
+    /// Polls a pool every `interval`, yielding a fresh snapshot each time.
+    ///
+    /// The first snapshot is fetched immediately. The public API has no push feed, so this
+    /// is a polling loop; a failed poll is yielded as an error and polling continues.
+    pub fn watch_pool<'a>(
+        &'a self,
+        chain: &'a str,
+        address: &'a str,
+        interval: Duration,
+    ) -> impl Stream<Item = Result<Whirlpool, OrcaError>> + 'a {
+        stream::unfold(Interval::new(interval), move |mut ticker| async move {
+            ticker.tick().await;
+            Some((self.get_pool_single(chain, address).await, ticker))
+        })
+    }
+
+    /// Like `watch_pool`, ending as soon as `cancel` fires, in the same way as
+    /// `pools_stream_with_cancel`.
+    pub fn watch_pool_with_cancel<'a>(
+        &'a self,
+        chain: &'a str,
+        address: &'a str,
+        interval: Duration,
+        cancel: CancellationToken,
+    ) -> impl Stream<Item = Result<Whirlpool, OrcaError>> + 'a {
+        until_cancelled(self.watch_pool(chain, address, interval), cancel)
+    }
+
+    /// Fetches protocol info for every chain in `chains` concurrently, returning one result per
+    /// chain in input order so a failure on one chain does not affect the others.
+    ///
+    /// `concurrency` caps the number of requests in flight; `None` sends them all at once.
+    pub async fn get_protocol_info_all(
+        &self,
+        chains: &[Chain],
+        concurrency: Option<usize>,
+    ) -> Vec<(Chain, Result<ProtocolInfo, OrcaError>)> {
+        let requests = chains
+            .iter()
+            .map(|&chain| async move { (chain, self.get_protocol_info(chain.as_str()).await) });
+        match concurrency {
+            Some(limit) => {
+                stream::iter(requests)
+                    .buffered(limit.max(1))
+                    .collect()
+                    .await
+            }
+            None => future::join_all(requests).await,
+        }
+    }
+
+    /// Checks which of `addresses` still exist, issuing at most `concurrency` requests at a time.
+    ///
+    /// A `404` or an empty response maps to `Ok(false)`; any other failure is kept as the
+    /// address's `Err` so it is not mistaken for a missing pool.
+    pub async fn check_pools_exist(
+        &self,
+        chain: &str,
+        addresses: &[&str],
+        concurrency: usize,
+    ) -> HashMap<String, Result<bool, OrcaError>> {
+        stream::iter(addresses)
+            .map(|address| async move {
+                let exists = match self.get_pool(chain, address).await {
+                    Ok(page) => Ok(!page.data.is_empty()),
+                    Err(OrcaError::Api { status: 404, .. } | OrcaError::NotFound { .. }) => {
+                        Ok(false)
+                    }
+                    Err(err) => Err(err),
+                };
+                (address.to_string(), exists)
+            })
+            .buffer_unordered(concurrency.max(1))
+            .collect()
+            .await
+    }
+
+    /// Fetches each pool in `addresses` with its own request, issuing at most `concurrency`
+    /// requests at a time.
+    ///
+    /// Results are returned in the order of `addresses`; a failed lookup does not affect the
+    /// others. A pool missing from its response is reported as `OrcaError::NotFound`.
+    pub async fn get_pools_concurrent(
+        &self,
+        chain: &str,
+        addresses: &[&str],
+        concurrency: usize,
+    ) -> Vec<Result<Whirlpool, OrcaError>> {
+        let mut results: Vec<_> =
+            stream::iter(addresses.iter().enumerate())
+                .map(|(index, &address)| async move {
+                    (index, self.get_pool_single(chain, address).await)
+                })
+                .buffer_unordered(concurrency.max(1))
+                .collect()
+                .await;
+        results.sort_unstable_by_key(|(index, _)| *index);
+        results.into_iter().map(|(_, pool)| pool).collect()
+    }
+
+    /// Fetches the pools at `addresses` through the `addresses` filter of `/pools`, following
+    /// pagination if the result spans several pages.
+    pub async fn get_pools_by_addresses(
+        &self,
+        chain: &str,
+        addresses: &[&str],
+    ) -> Result<Vec<Whirlpool>, OrcaError> {
+        if addresses.is_empty() {
+            return Ok(Vec::new());
+        }
+        let params = GetPoolsParams {
+            addresses: Some(addresses),
+            ..Default::default()
+        };
+        self.pools_stream(chain, params).try_collect().await
+    }
+
+    /// Sums the fees earned over `period` by the pools at `addresses`.
+    ///
+    /// The pools are fetched in one request with `period` stats included. Pools that report
+    /// no stats for `period` contribute zero.
+    pub async fn sum_pool_fees(
+        &self,
+        chain: &str,
+        addresses: &[&str],
+        period: TimePeriod,
+    ) -> Result<Decimal, OrcaError> {
+        if addresses.is_empty() {
+            return Ok(Decimal::ZERO);
+        }
+        let params = GetPoolsParams {
+            addresses: Some(addresses),
+            stats: Some(std::slice::from_ref(&period)),
+            ..Default::default()
+        };
+        let pools: Vec<Whirlpool> = self.pools_stream(chain, params).try_collect().await?;
+        pools
+            .iter()
+            .filter_map(|pool| pool.stats.get(&period))
+            .try_fold(Decimal::ZERO, |total, stats| {
+                Ok(total + parse_decimal("fees", &stats.fees)?)
+            })
+    }
+
+    /// Fetches the tokens for `mints` in one request through the comma-separated `tokens`
+    /// filter of `/tokens`, following pagination if needed.
+    pub async fn get_tokens_by_mints(
+        &self,
+        chain: &str,
+        mints: &[&str],
+    ) -> Result<Vec<Token>, OrcaError> {
+        if mints.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.collect_tokens(chain, Some(&mints.join(","))).await
+    }
+
+    /// Fetches every pool matching `params`, draining all pages.
+    ///
+    /// Cursor pagination over a dataset that changes mid-scan can return an item on two
+    /// pages or skip one. Pools are deduplicated by address, keeping the first occurrence,
+    /// but a skipped pool is not recovered, so the result is not guaranteed to be a complete
+    /// or repeatable snapshot.
+    ///
+    /// When `sort_by` is `None` no sort is pinned, because the API has no stable sort key to
+    /// pin: it cannot order pools by address, and the keys it accepts (`tvl`, `volume`,
+    /// `yieldOverTvl`) are live metrics that move during the scan. Passing one of them makes
+    /// drift more likely, not less.
+    pub async fn get_all_pools(
+        &self,
+        chain: &str,
+        params: GetPoolsParams<'_>,
+    ) -> Result<Vec<Whirlpool>, OrcaError> {
+        let mut seen = HashSet::new();
+        self.pools_stream(chain, params)
+            .try_filter(|pool| std::future::ready(seen.insert(pool.address.clone())))
+            .try_collect()
+            .await
+    }
+
+    /// Fetches every pool matching `params` into a map keyed by pool address.
+    ///
+    /// Pages are fetched until the last one or until `max_pages` pages have been read,
+    /// whichever comes first. The cursor of the next unread page is returned with the map;
+    /// it is `None` once every page has been read, and otherwise can be passed as `next` to
+    /// resume the scan. A pool that appears on two pages keeps its first occurrence, like
+    /// `get_all_pools`. Returns `OrcaError::Validation` if `max_pages` is zero.
+    pub async fn get_pool_map(
+        &self,
+        chain: &str,
+        params: GetPoolsParams<'_>,
+        max_pages: u32,
+    ) -> Result<(HashMap<String, Whirlpool>, Option<Cursor>), OrcaError> {
+        if max_pages == 0 {
+            return Err(OrcaError::Validation(
+                "`max_pages` must be at least 1".to_string(),
+            ));
+        }
+        let mut pools = HashMap::new();
+        let mut next = params.next.cloned();
+        for _ in 0..max_pages {
+            let page = self
+                .get_pools(
+                    chain,
+                    GetPoolsParams {
+                        next: next.as_ref(),
+                        ..params
+                    },
+                )
+                .await?;
+            if page.data.is_empty() {
+                return Ok((pools, None));
+            }
+            for pool in page.data {
+                pools.entry(pool.address.to_string()).or_insert(pool);
+            }
+            next = page.meta.next;
+            if next.is_none() {
+                break;
+            }
+        }
+        Ok((pools, next))
+    }
+
+    /// Fetches the address of every pool matching `params`, draining all pages.
+    ///
+    /// `/pools` has no field projection, so full pools are still downloaded, but only their
+    /// addresses are deserialized, which keeps memory flat when indexing every pool.
+    /// Addresses are deduplicated, keeping the first occurrence, like `get_all_pools`.
+    pub async fn get_pool_addresses(
+        &self,
+        chain: &str,
+        params: GetPoolsParams<'_>,
+    ) -> Result<Vec<String>, OrcaError> {
+        let mut seen = HashSet::new();
+        let mut addresses = Vec::new();
+        let mut next = params.next.cloned();
+        loop {
+            let url = self.pools_url(
+                chain,
+                GetPoolsParams {
+                    next: next.as_ref(),
+                    ..params
+                },
+            )?;
+            let page: Paginated<PoolAddress> = self.get_json(url).await?;
+            let done = page.data.is_empty();
+            for pool in page.data {
+                if seen.insert(pool.address.clone()) {
+                    addresses.push(pool.address);
+                }
+            }
+            next = page.meta.next;
+            if done || next.is_none() {
+                return Ok(addresses);
+            }
+        }
+    }
+
+    /// Fetches every pool that has `mint` as token A or token B, draining all pages.
+    ///
+    /// The pools are filtered server-side through the `token` parameter of `/pools`.
+    pub async fn find_pools_with_token(
+        &self,
+        chain: &str,
+        mint: &str,
+    ) -> Result<Vec<Whirlpool>, OrcaError> {
+        let params = GetPoolsParams {
+            token: Some(std::slice::from_ref(&mint)),
+            ..Default::default()
+        };
+        let mut pools = self.get_all_pools(chain, params).await?;
+        pools.retain(|pool| pool.token_mint_a == mint || pool.token_mint_b == mint);
+        Ok(pools)
+    }
+
+    /// Returns up to `limit` pools that have `mint` as token A or token B, deepest first.
+    ///
+    /// A convenience over `get_pools` that combines the `token` filter, a descending TVL
+    /// sort and a page size of `limit`, so it makes a single request. `limit` must be
+    /// between 1 and `MAX_PAGE_SIZE`.
+    pub async fn best_pools_for_token(
+        &self,
+        chain: &str,
+        mint: &str,
+        limit: u32,
+    ) -> Result<Vec<Whirlpool>, OrcaError> {
+        let params = GetPoolsParams {
+            token: Some(std::slice::from_ref(&mint)),
+            sort_by: Some("tvl"),
+            sort_direction: Some("desc"),
+            size: Some(limit),
+            ..Default::default()
+        };
+        let mut pools = self.get_pools(chain, params).await?.data;
+        pools.retain(|pool| pool.token_mint_a == mint || pool.token_mint_b == mint);
+        pools.truncate(limit as usize);
+        Ok(pools)
+    }
+
+    /// Fetches every token, draining all pages and deduplicating by mint address.
+    ///
+    /// As with `get_all_pools`, a token skipped by a listing that changes mid-scan is not
+    /// recovered. `/tokens` takes no sort parameter, so the order cannot be pinned to a
+    /// stable key.
+    pub async fn get_all_tokens(&self, chain: &str) -> Result<Vec<Token>, OrcaError> {
+        self.collect_tokens(chain, None).await
+    }
+
+    /// Writes every token as CSV to `writer`.
+    ///
+    /// Columns are `address`, `symbol`, `name`, `decimals`, `price_usdc`, `supply`,
+    /// `has_mint_authority` and `has_freeze_authority`. Symbol and name come from the token's
+    /// metadata and are left empty when it does not provide them.
+    #[cfg(feature = "csv")]
+    pub async fn export_tokens_csv<W: std::io::Write>(
+        &self,
+        chain: &str,
+        writer: W,
+    ) -> Result<(), OrcaError> {
+        let tokens = self.get_all_tokens(chain).await?;
+        let mut csv = csv::Writer::from_writer(writer);
+        csv.write_record([
+            "address",
+            "symbol",
+            "name",
+            "decimals",
+            "price_usdc",
+            "supply",
+            "has_mint_authority",
+            "has_freeze_authority",
+        ])?;
+        for token in &tokens {
+            let metadata = token.parsed_metadata().unwrap_or_default();
+            csv.write_record([
+                token.address.as_str(),
+                metadata.symbol.as_deref().unwrap_or_default(),
+                metadata.name.as_deref().unwrap_or_default(),
+                &token.decimals.to_string(),
+                &token.price_usdc,
+                &token.supply,
+                &token.mint_authority.is_some().to_string(),
+                &token.freeze_authority.is_some().to_string(),
+            ])?;
+        }
+        csv.flush().map_err(csv::Error::from)?;
+        Ok(())
+    }
+
+    /// Fetches every pool matching `params` and counts them per TVL bucket.
+    ///
+    /// See `models::tvl_histogram` for how `buckets` are interpreted.
+    pub async fn get_tvl_histogram(
+        &self,
+        chain: &str,
+        params: GetPoolsParams<'_>,
+        buckets: &[Decimal],
+    ) -> Result<Vec<(Decimal, usize)>, OrcaError> {
+        let pools: Vec<Whirlpool> = self.pools_stream(chain, params).try_collect().await?;
+        tvl_histogram(&pools, buckets)
+    }
+
+    /// Fetches every pool trading `mint` and ranks them by `Whirlpool::lp_opportunity_score`,
+    /// best first.
+    ///
+    /// Pools are matched on either side of the pair. The API filters the listing by `mint`, so
+    /// this makes one request per page of matching pools.
+    pub async fn rank_lp_opportunities(
+        &self,
+        chain: &str,
+        mint: &str,
+    ) -> Result<Vec<(Whirlpool, Decimal)>, OrcaError> {
+        let stats = [TimePeriod::H24];
+        let params = GetPoolsParams {
+            token: Some(std::slice::from_ref(&mint)),
+            stats: Some(&stats),
+            ..Default::default()
+        };
+        let pools: Vec<Whirlpool> = self
+            .pools_stream(chain, params)
+            .try_filter(|pool| {
+                future::ready(pool.token_mint_a == mint || pool.token_mint_b == mint)
+            })
+            .try_collect()
+            .await?;
+        let mut ranked = pools
+            .into_iter()
+            .map(|pool| {
+                let score = pool.lp_opportunity_score()?;
+                Ok((pool, score))
+            })
+            .collect::<Result<Vec<_>, OrcaError>>()?;
+        ranked.sort_by(|(_, a), (_, b)| b.cmp(a));
+        Ok(ranked)
+    }
+
+    /// Returns up to `limit` pools with at least `min_tvl` USDC of liquidity, sorted by
+    /// yield over TVL, highest first.
+    pub async fn get_top_yield_pools(
+        &self,
+        chain: &str,
+        min_tvl: f64,
+        limit: u32,
+    ) -> Result<Vec<Whirlpool>, OrcaError> {
+        let params = GetPoolsParams {
+            sort_by: Some("yieldOverTvl"),
+            sort_direction: Some("desc"),
+            min_tvl: Some(min_tvl),
+            size: Some(limit),
+            ..Default::default()
+        };
+        let mut pools = self.get_pools(chain, params).await?.data;
+        pools.truncate(limit as usize);
+        Ok(pools)
+    }
+
+    /// Drains the `/tokens` pages for an optional `tokens` filter, deduplicating by address.
+    async fn collect_tokens(
+        &self,
+        chain: &str,
+        tokens_filter: Option<&str>,
+    ) -> Result<Vec<Token>, OrcaError> {
+        let mut seen = HashSet::new();
+        let mut tokens = Vec::new();
+        let mut next: Option<Cursor> = None;
+        loop {
+            let page = self
+                .get_tokens(chain, next.as_ref(), None, None, None, None, tokens_filter)
+                .await?;
+            let done = page.data.is_empty();
+            tokens.extend(
+                page.data
+                    .into_iter()
+                    .filter(|token| seen.insert(token.address.clone())),
+            );
+            next = page.meta.next;
+            if done || next.is_none() {
+                return Ok(tokens);
+            }
+        }
+    }
+
+    /// Builds a `/pools` URL for `params` merged with the client's default pool parameters.
+    fn pools_url(&self, chain: &str, params: GetPoolsParams<'_>) -> Result<Url, OrcaError> {
+        endpoints::pools(
+            &self.base_url,
+            chain,
+            &params.or_defaults(self.default_pool_params),
+        )
+    }
+
+    /// Turns an empty page into `OrcaError::NotFound` when `empty_paginated_is_error` is set.
+    fn check_not_empty<T>(
+        &self,
+        page: Paginated<T>,
+        resource: &'static str,
+        id: &str,
+    ) -> Result<Paginated<T>, OrcaError> {
+        if self.empty_paginated_is_error && page.data.is_empty() {
+            return Err(OrcaError::NotFound {
+                resource,
+                id: id.to_string(),
+            });
+        }
+        Ok(page)
+    }
+
+    /// Adds `len` bytes to the received-bytes counter.
+    fn record_bytes(&self, len: usize) {
+        self.bytes_received.fetch_add(len as u64, Ordering::Relaxed);
+    }
+
+    /// Sends a GET request and deserializes the JSON response body.
+    async fn get_json<T: DeserializeOwned>(&self, url: Url) -> Result<T, OrcaError> {
+        let body = self.get_body(url).await?;
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    /// Returns the response body for `url`, from the cache when a fresh entry exists.
+    async fn get_body(&self, url: Url) -> Result<Bytes, OrcaError> {
+        if let Some(body) = self
+            .cache
+            .as_ref()
+            .and_then(|cache| cache.get(url.as_str()))
+        {
+            return Ok(body);
+        }
+        let key = url.to_string();
+        #[cfg(feature = "record-replay")]
+        let request = replay::request_key(&self.base, &url);
+        let body = self.fetch(url).await?;
+        #[cfg(feature = "record-replay")]
+        if let Some(dir) = &self.record_dir {
+            replay::record(dir, request, &body).await?;
+        }
+        if let Some(cache) = &self.cache {
+            cache.insert(key, body.clone());
+        }
+        Ok(body)
+    }
+
+    /// Fetches the response body for `url`, retrying retryable failures unless the request
+    /// options disable it.
+    async fn fetch(&self, url: Url) -> Result<Bytes, OrcaError> {
+        Ok(self.fetch_with_meta(url).await?.0)
+    }
+
+    /// Fetches `url` through the retry policy, returning the body along with metadata about
+    /// the successful response.
+    async fn fetch_with_meta(&self, url: Url) -> Result<(Bytes, ResponseMeta), OrcaError> {
+        let max_retries = if self.options.no_retry {
+            0
+        } else {
+            self.retry_policy.max_retries
+        };
+        let mut attempt = 0;
+        loop {
+            let started = Instant::now();
+            match self.send_with_failover(&url).await {
+                Ok((body, headers)) => {
+                    let meta = ResponseMeta {
+                        status: headers.status,
+                        rate_limit_limit: headers.rate_limit_limit,
+                        rate_limit_remaining: headers.rate_limit_remaining,
+                        rate_limit_reset: headers.rate_limit_reset,
+                        latency: started.elapsed(),
+                    };
+                    return Ok((body, meta));
+                }
+                Err(err) if attempt < max_retries && err.is_retryable() => {
+                    let delay = match err.retry_after() {
+                        Some(delay) if delay > self.retry_policy.max_backoff => return Err(err),
+                        Some(delay) => delay,
+                        None => self.retry_policy.backoff(attempt),
+                    };
+                    rt::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// Logs a warning if a request to `endpoint` took longer than the slow request threshold.
+    #[cfg(any(feature = "tracing", feature = "debug"))]
+    fn warn_if_slow(&self, endpoint: &str, elapsed: Duration) {
+        let threshold = match self.slow_request_threshold {
+            Some(threshold) if elapsed > threshold => threshold,
+            _ => return,
+        };
+        #[cfg(feature = "tracing")]
+        tracing::warn!(
+            endpoint,
+            elapsed_ms = elapsed.as_millis() as u64,
+            threshold_ms = threshold.as_millis() as u64,
+            "slow request"
+        );
+        #[cfg(feature = "debug")]
+        log::warn!(
+            "slow request to {}: took {:?}, threshold {:?}",
+            endpoint,
+            elapsed,
+            threshold
+        );
+    }
+
+    /// Renders the query string of `url` with sensitive values replaced by `[REDACTED]`.
+    #[cfg(feature = "tracing")]
+    fn redacted_query(&self, url: &Url) -> String {
+        url.query_pairs()
+            .map(|(key, value)| {
+                let lower = key.to_ascii_lowercase();
+                let sensitive = SENSITIVE_QUERY_PARAMS.contains(&lower.as_str())
+                    || self.redacted_params.contains(&lower);
+                if sensitive {
+                    format!("{key}=[REDACTED]")
+                } else {
+                    format!("{key}={value}")
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+
+    /// Sends a request to `url`, then to the same path on each fallback host in turn while the
+    /// failure is one that `is_failover_error` accepts.
+    ///
+    /// With fallback hosts configured, a `url` outside the base URL is an
+    /// `OrcaError::Validation`, since it has no counterpart on the other hosts.
+    async fn send_with_failover(&self, url: &Url) -> Result<(Bytes, ResponseHeaders), OrcaError> {
+        if self.fallback_base_urls.is_empty() {
+            return self.send(url.clone()).await;
+        }
+        let path = endpoints::relative_path(&self.base, url).ok_or_else(|| {
+            OrcaError::Validation(format!("{url} is not under the base URL {}", self.base))
+        })?;
+        let mut result = self.send(url.clone()).await;
+        for fallback in self.fallback_base_urls.iter() {
+            match &result {
+                Err(err) if is_failover_error(err) => {}
+                _ => break,
+            }
+            let mut target = fallback.clone();
+            target.set_path(&format!("{}{path}", fallback.path().trim_end_matches('/')));
+            target.set_query(url.query());
+            result = self.send(target).await;
+        }
+        result
+    }
+
+    /// Sends a single GET request through the transport, which turns non-success statuses
+    /// into `OrcaError::Api`.
+    async fn send(&self, url: Url) -> Result<(Bytes, ResponseHeaders), OrcaError> {
+        if let Some(limit) = self.byte_quota {
+            let received = self.bytes_received();
+            if received >= limit {
+                return Err(OrcaError::QuotaExceeded { limit, received });
+            }
+        }
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire().await;
+        }
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            endpoint = url.path(),
+            query = %self.redacted_query(&url),
+            "sending request"
+        );
+        if let Some(observer) = &self.observer {
+            observer.on_request(&url);
+        }
+        let started = Instant::now();
+        let result = self.transport.get_with_headers(url.clone()).await;
+        let elapsed = started.elapsed();
+        #[cfg(any(feature = "tracing", feature = "debug"))]
+        self.warn_if_slow(url.path(), elapsed);
+        if let Some(observer) = &self.observer {
+            match &result {
+                Ok((_, headers)) => observer.on_response(&url, headers.status, elapsed),
+                Err(err) => observer.on_error(&url, err),
+            }
+        }
+        match &result {
+            Ok((body, _)) => self.record_bytes(body.len()),
+            Err(OrcaError::Api { body, .. }) => self.record_bytes(body.len()),
+            Err(_) => {}
+        }
+        result
+    }
+}
+
+/// Parses `base_url`, rejecting URLs such as `mailto:` ones that cannot have a path.
+fn parse_base_url(base_url: &str) -> Result<Url, OrcaError> {
+    let url = Url::parse(base_url)?;
+    if url.cannot_be_a_base() {
+        return Err(ParseError::RelativeUrlWithCannotBeABaseBase.into());
+    }
+    Ok(url)
+}
+
+/// Joins `host` and the `api_version` path into a base URL.
+fn host_base_url(host: &str, api_version: &str) -> String {
+    let host = host.trim_end_matches('/');
+    match api_version.trim_matches('/') {
+        "" => host.to_string(),
+        version => format!("{}/{}", host, version),
+    }
+}
+
+/// Returns `true` if `err` should send the request on to a fallback host: a connection
+/// error, a timeout or a `5xx` response.
+fn is_failover_error(err: &OrcaError) -> bool {
+    match err {
+        OrcaError::Api { status, .. } => *status >= 500,
+        OrcaError::Http(_) => err.is_retryable(),
+        _ => false,
+    }
+}
+
+/// Forwards the items of `stream` until `cancel` fires, then drops `stream` and ends.
+fn until_cancelled<S: Stream>(stream: S, cancel: CancellationToken) -> impl Stream<Item = S::Item> {
+    stream::unfold(
+        (Box::pin(stream), cancel),
+        |(mut stream, cancel)| async move {
+            tokio::select! {
+                biased;
+                _ = cancel.cancelled() => None,
+                item = stream.next() => item.map(|item| (item, (stream, cancel))),
+            }
+        },
+    )
+    .fuse()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::fixtures::{self, ORCA_MINT, SOL_USDC_POOL, USDC_MINT};
+    use crate::models::models::WSOL_MINT;
+    use mockito::{mock, Matcher};
+
+    #[test]
+    fn test_base_url_composition() {
+        let default = OrcaClient::new();
+        assert_eq!(default.base_url, "https://api.orca.so/v2");
+
+        let staging = OrcaClient::builder()
+            .with_host("https://staging.example.com/")
+            .with_api_version("/v3")
+            .build()
+            .unwrap();
+        assert_eq!(staging.base_url, "https://staging.example.com/v3");
+
+        let unversioned = OrcaClient::builder()
+            .with_host("http://localhost:1234")
+            .with_api_version("")
+            .build()
+            .unwrap();
+        assert_eq!(unversioned.base_url, "http://localhost:1234");
+
+        let overridden = OrcaClient::builder()
+            .with_base_url("http://localhost:1234/custom")
+            .with_api_version("v3")
+            .build()
+            .unwrap();
+        assert_eq!(overridden.base_url, "http://localhost:1234/custom");
+    }
+
+    #[tokio::test]
+    async fn test_base_url_trailing_slash() {
+        let m = mock("GET", "/solana/protocol/token/total_supply")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"total_supply": "1000"}"#)
+            .expect(2)
+            .create();
+        let plain = OrcaClient::with_base_url(&mockito::server_url());
+        let slashed = OrcaClient::with_base_url(&format!("{}/", mockito::server_url()));
+        assert_eq!(
+            endpoints::protocol(&slashed.base_url, "solana").unwrap(),
+            endpoints::protocol(&plain.base_url, "solana").unwrap()
+        );
+        for client in [plain, slashed] {
+            assert_eq!(
+                client
+                    .get_total_supply("solana")
+                    .await
+                    .unwrap()
+                    .total_supply,
+                "1000"
+            );
+        }
+        m.assert();
+    }
+
+    #[tokio::test]
+    async fn test_base_url_path_prefix() {
+        let _m = mock("GET", "/orca/v2/solana/protocol/token/circulating_supply")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"circulating_supply": "500"}"#)
+            .create();
+        let client = OrcaClient::builder()
+            .with_host(&format!("{}/orca/", mockito::server_url()))
+            .build()
+            .unwrap();
+        assert_eq!(
+            client
+                .get_circulating_supply("solana")
+                .await
+                .unwrap()
+                .circulating_supply,
+            "500"
+        );
+
+        let gateway = OrcaClient::with_base_url("https://gw.internal/orca/v2/");
+        let address = "Czfq3xZZDmsdGdUyrNLtRhGc47cXcZtLG4crryfu44zE";
+        assert_eq!(
+            endpoints::pool(&gateway.base_url, "solana", address)
+                .unwrap()
+                .as_str(),
+            format!("https://gw.internal/orca/v2/solana/pools/{}", address)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_api_version_path() {
+        let _m = mock("GET", "/v3/solana/protocol")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "fees24hUsdc": "1",
+                    "revenue24hUsdc": "1",
+                    "tvl": "1",
+                    "volume24hUsdc": "1"
+                }"#,
+            )
+            .create();
+        let client = OrcaClient::builder()
+            .with_host(&mockito::server_url())
+            .with_api_version("v3")
+            .build()
+            .unwrap();
+        assert_eq!(client.get_protocol_info("solana").await.unwrap().tvl, "1");
+    }
+
+    #[test]
+    fn test_get_pools_params_validate() {
+        assert!(GetPoolsParams::default().validate().is_ok());
+        let (a, b) = (Cursor::from("a"), Cursor::from("b"));
+        let valid = GetPoolsParams {
+            size: Some(MAX_PAGE_SIZE),
+            min_tvl: Some(0.0),
+            min_locked_liquidity_percent: Some(100.0),
+            next: Some(&a),
+            sort_direction: Some("desc"),
+            ..Default::default()
+        };
+        assert!(valid.validate().is_ok());
+
+        let invalid = [
+            GetPoolsParams {
+                size: Some(0),
+                ..Default::default()
+            },
+            GetPoolsParams {
+                size: Some(MAX_PAGE_SIZE + 1),
+                ..Default::default()
+            },
+            GetPoolsParams {
+                min_tvl: Some(-1.0),
+                ..Default::default()
+            },
+            GetPoolsParams {
+                min_volume: Some(f64::NAN),
+                ..Default::default()
+            },
+            GetPoolsParams {
+                min_locked_liquidity_percent: Some(150.0),
+                ..Default::default()
+            },
+            GetPoolsParams {
+                next: Some(&a),
+                previous: Some(&b),
+                ..Default::default()
+            },
+            GetPoolsParams {
+                sort_direction: Some("up"),
+                ..Default::default()
+            },
+        ];
+        for params in invalid {
+            assert!(matches!(params.validate(), Err(OrcaError::Validation(_))));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_pools_rejects_invalid_params() {
+        let m = mock("GET", "/solana/pools").expect(0).create();
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        let params = GetPoolsParams {
+            min_tvl: Some(-5.0),
+            ..Default::default()
+        };
+        let result = client.get_pools("solana", params).await;
+        assert!(matches!(result, Err(OrcaError::Validation(_))));
+        m.assert();
+    }
+
+    #[tokio::test]
+    async fn test_get_protocol_info() {
+        let _m = mock("GET", "/solana/protocol")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "fees24hUsdc": "317428.0521046",
+                    "revenue24hUsdc": "41265.646773",
+                    "tvl": "230551269.0085",
+                    "volume24hUsdc": "552567794.7830"
+                }"#,
+            )
+            .create();
+
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        let result = client.get_protocol_info("solana").await;
+
+        assert!(result.is_ok());
+        let protocol_info = result.unwrap();
+        assert_eq!(protocol_info.fees_24h_usdc, "317428.0521046");
+    }
+
+    #[tokio::test]
+    async fn test_byte_quota() {
+        let body = r#"{
+            "fees24hUsdc": "317428.0521046",
+            "revenue24hUsdc": "41265.646773",
+            "tvl": "230551269.0085",
+            "volume24hUsdc": "552567794.7830"
+        }"#;
+        let len = body.len() as u64;
+        let m = mock("GET", "/solana/protocol")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(body)
+            .expect(2)
+            .create();
+
+        let client = OrcaClient::builder()
+            .with_base_url(&mockito::server_url())
+            .with_byte_quota(len + 1)
+            .build()
+            .unwrap();
+        assert_eq!(client.bytes_received(), 0);
+        client.get_protocol_info("solana").await.unwrap();
+        assert_eq!(client.bytes_received(), len);
+        client.get_protocol_info("solana").await.unwrap();
+        assert_eq!(client.bytes_received(), 2 * len);
+
+        match client.get_protocol_info("solana").await {
+            Err(OrcaError::QuotaExceeded { limit, received }) => {
+                assert_eq!(limit, len + 1);
+                assert_eq!(received, 2 * len);
+            }
+            other => panic!("expected QuotaExceeded, got {:?}", other),
+        }
+        m.assert();
+    }
+
+    #[tokio::test]
+    async fn test_compression() {
+        use flate2::write::GzEncoder;
+        use flate2::Compression;
+        use std::io::Write;
+
+        // 100 pools whose numeric fields differ, as on a real page, so the ratio is not
+        // inflated by identical entries.
+        let pools = (1..=100u64)
+            .map(|i| {
+                let n = i.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+                fixtures::merge(
+                    fixtures::whirlpool_json(),
+                    serde_json::json!({
+                        "feeGrowthGlobalA": (n >> 8).to_string(),
+                        "feeGrowthGlobalB": (n >> 20).to_string(),
+                        "liquidity": (n >> 16).to_string(),
+                        "sqrtPrice": (n >> 1).to_string(),
+                        "tickCurrentIndex": (n % 400_000) as i64 - 200_000,
+                        "updatedSlot": 338_000_000 + (n % 1_000_000),
+                        "price": format!("{}.{}", n % 10_000, n % 1_000_000),
+                        "tokenBalanceA": format!("{}.{}", n >> 40, n % 1_000_000_000),
+                        "tokenBalanceB": format!("{}.{}", n >> 36, n % 1_000_000),
+                        "tvlUsdc": format!("{}.{}", n >> 38, n % 100),
+                        "stats": { "24h": {
+                            "fees": format!("{}.{}", n >> 48, n % 100),
+                            "volume": format!("{}.{}", n >> 44, n % 100),
+                            "yieldOverTvl": format!("0.{}", n % 100_000)
+                        } }
+                    }),
+                )
+            })
+            .collect();
+        let body = fixtures::paginated(pools);
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(body.as_bytes()).unwrap();
+        let compressed = encoder.finish().unwrap();
+        // Measured at about 13x; leave headroom for fixture changes.
+        assert!(compressed.len() < body.len() / 8);
+
+        let gzipped = mock("GET", "/solana/pools")
+            .match_query(Matcher::Missing)
+            .match_header("accept-encoding", Matcher::Regex("gzip".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_header("content-encoding", "gzip")
+            .with_body(&compressed)
+            .create();
+        let plain = mock("GET", "/solana/pools")
+            .match_query(Matcher::Missing)
+            .match_header("accept-encoding", Matcher::Missing)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(&body)
+            .create();
+
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        let page = client
+            .get_pools("solana", GetPoolsParams::default())
+            .await
+            .unwrap();
+        assert_eq!(page.data.len(), 100);
+        assert_eq!(client.bytes_received(), body.len() as u64);
+        gzipped.assert();
+
+        let uncompressed = OrcaClient::builder()
+            .with_base_url(&mockito::server_url())
+            .with_compression(false)
+            .build()
+            .unwrap();
+        let page = uncompressed
+            .get_pools("solana", GetPoolsParams::default())
+            .await
+            .unwrap();
+        assert_eq!(page.data.len(), 100);
+        plain.assert();
+    }
+
+    #[tokio::test]
+    async fn test_get_pools_with_meta() {
+        let _m = mock("GET", "/solana/pools?size=1")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_header("x-ratelimit-limit", "100")
+            .with_header("x-ratelimit-remaining", "42")
+            .with_header("x-ratelimit-reset", "1746749090")
+            .with_body(fixtures::paginated(vec![fixtures::whirlpool_json()]))
+            .create();
+        let _bare = mock("GET", "/solana/pools?size=2")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![]))
+            .create();
+
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        let params = GetPoolsParams {
+            size: Some(1),
+            ..Default::default()
+        };
+        let (page, meta) = client.get_pools_with_meta("solana", params).await.unwrap();
+        assert_eq!(page.data.len(), 1);
+        assert_eq!(meta.status, 200);
+        assert_eq!(meta.rate_limit_limit, Some(100));
+        assert_eq!(meta.rate_limit_remaining, Some(42));
+        assert_eq!(meta.rate_limit_reset, Some(1746749090));
+        assert!(meta.latency > Duration::ZERO);
+
+        let params = GetPoolsParams {
+            size: Some(2),
+            ..Default::default()
+        };
+        let (_, meta) = client.get_pools_with_meta("solana", params).await.unwrap();
+        assert_eq!(meta.rate_limit_remaining, None);
+    }
+
+    #[tokio::test]
+    async fn test_get_token_info() {
+        let _m = mock("GET", "/solana/protocol/token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "circulatingSupply": "53275182.419413",
+                    "description": "Orca Token",
+                    "imageUrl": "https://raw.githubusercontent.com/solana-labs/token-list/main/assets/mainnet/orcaEKTdK7LKz57vaAYr9QeNsVEPfiu6QeMU1kektZE/logo.png",
+                    "name": "Orca",
+                    "price": "1.6767140",
+                    "stats": {
+                        "24h": {
+                            "volume": "594947.6898176792"
+                        }
+                    },
+                    "symbol": "ORCA",
+                    "totalSupply": "99999712.243267"
+                }"#,
+            )
+            .create();
+
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        let result = client.get_token_info("solana").await;
+
+        assert!(result.is_ok());
+        let token_info = result.unwrap();
+        assert_eq!(token_info.name, "Orca");
+    }
+
+    #[tokio::test]
+    async fn test_get_circulating_supply() {
+        let _m = mock("GET", "/solana/protocol/token/circulating_supply")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"circulating_supply": "53275183"}"#)
+            .create();
+
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        let result = client.get_circulating_supply("solana").await;
+
+        assert!(result.is_ok());
+        let circulating_supply = result.unwrap();
+        assert_eq!(circulating_supply.circulating_supply, "53275183");
+    }
+
+    #[tokio::test]
+    async fn test_get_token_supply() {
+        let _circulating = mock("GET", "/eclipse/protocol/token/circulating_supply")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"circulating_supply": "53275183.5"}"#)
+            .create();
+        let _total = mock("GET", "/eclipse/protocol/token/total_supply")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"total_supply": "99999713"}"#)
+            .create();
+
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        let supply = client.get_token_supply("eclipse").await.unwrap();
+        assert_eq!(supply.circulating, Decimal::new(532751835, 1));
+        assert_eq!(supply.total, Decimal::from(99999713));
+    }
+
+    #[tokio::test]
+    async fn test_get_total_supply() {
+        let _m = mock("GET", "/solana/protocol/token/total_supply")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"total_supply": "99999713"}"#)
+            .create();
+
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        let result = client.get_total_supply("solana").await;
+
+        assert!(result.is_ok());
+        let total_supply = result.unwrap();
+        assert_eq!(total_supply.total_supply, "99999713");
+    }
+
+    #[tokio::test]
+    async fn test_get_tokens() {
+        let _m = mock("GET", "/solana/tokens?size=1")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "data": [
+                        {
+                            "address": "So11111111111111111111111111111111111111112",
+                            "decimals": 9,
+                            "extensions": "{}",
+                            "freezeAuthority": null,
+                            "isInitialized": true,
+                            "metadata": "{}",
+                            "mintAuthority": null,
+                            "priceUsdc": "130.0",
+                            "stats": "{}",
+                            "supply": "1000000000",
+                            "tags": "[]",
+                            "tokenProgram": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+                            "updatedAt": "2025-05-09T00:04:50.745163Z",
+                            "updatedEpoch": 784
+                        }
+                    ],
+                    "meta": {
+                        "next": "some-next-cursor",
+                        "previous": null
+                    }
+                }"#,
+            )
+            .create();
+
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        let result = client
+            .get_tokens("solana", None, None, Some(1), None, None, None)
+            .await;
+        assert!(result.is_ok());
+        let tokens = result.unwrap();
+        assert_eq!(tokens.data.len(), 1);
+        assert_eq!(
+            tokens.data[0].address,
+            "So11111111111111111111111111111111111111112"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_tokens() {
+        let _m = mock("GET", "/solana/tokens/search?q=sol")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "data": [],
+                    "meta": {
+                        "next": null,
+                        "previous": null
+                    }
+                }"#,
+            )
+            .create();
+
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        let result = client.search_tokens("solana", "sol").await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_search_tokens_with_params() {
+        let _m = mock("GET", "/solana/tokens/search")
+            .match_query(Matcher::Exact(
+                "q=orca&next=cursor-1&size=25&sortBy=volume&sortDirection=desc".to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![fixtures::token_json(ORCA_MINT)]))
+            .create();
+
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        let cursor = Cursor::from("cursor-1");
+        let params = SearchTokensParams {
+            q: "orca",
+            next: Some(&cursor),
+            size: Some(25),
+            sort_by: Some("volume"),
+            sort_direction: Some("desc"),
+        };
+        let page = client
+            .search_tokens_with_params("solana", params)
+            .await
+            .unwrap();
+        assert_eq!(page.data[0].address, ORCA_MINT);
+    }
+
+    #[tokio::test]
+    async fn test_get_token() {
+        let _m = mock(
+            "GET",
+            "/solana/tokens/So11111111111111111111111111111111111111112",
+        )
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+                "data": [],
+                "meta": {
+                    "next": null,
+                    "previous": null
+                }
+            }"#,
+        )
+        .create();
+
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        let result = client
+            .get_token("solana", "So11111111111111111111111111111111111111112")
+            .await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_get_lock_info() {
+        let _m = mock(
+            "GET",
+            "/solana/lock/Czfq3xZZDmsdGdUyrNLtRhGc47cXcZtLG4crryfu44zE",
+        )
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"[
+                {
+                    "lockedPercentage": "0.7",
+                    "name": "Whirlpool-Lock"
+                }
+            ]"#,
+        )
+        .create();
+
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        let result = client
+            .get_lock_info("solana", "Czfq3xZZDmsdGdUyrNLtRhGc47cXcZtLG4crryfu44zE")
+            .await;
+        assert!(result.is_ok());
+        let lock_info = result.unwrap();
+        assert_eq!(lock_info.len(), 1);
+        assert_eq!(lock_info[0].name, "Whirlpool-Lock");
+    }
+
+    #[tokio::test]
+    async fn test_get_total_locked_percent() {
+        let _locked = mock("GET", format!("/solana/lock/{}", USDC_MINT).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!([
+                    { "lockedPercentage": "0.7", "name": "Whirlpool-Lock" },
+                    { "lockedPercentage": "0.15", "name": "Team-Lock" }
+                ])
+                .to_string(),
+            )
+            .create();
+        let _unlocked = mock("GET", format!("/solana/lock/{}", ORCA_MINT).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body("[]")
+            .create();
+        let _malformed = mock("GET", format!("/solana/lock/{}", WSOL_MINT).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"[{ "lockedPercentage": "", "name": "Whirlpool-Lock" }]"#)
+            .create();
+
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        assert_eq!(
+            client
+                .get_total_locked_percent("solana", USDC_MINT)
+                .await
+                .unwrap(),
+            Decimal::new(85, 2)
+        );
+        assert_eq!(
+            client
+                .get_total_locked_percent("solana", ORCA_MINT)
+                .await
+                .unwrap(),
+            Decimal::ZERO
+        );
+        assert!(matches!(
+            client.get_total_locked_percent("solana", WSOL_MINT).await,
+            Err(OrcaError::InvalidNumber { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_pools_eclipse() {
+        let _m = mock("GET", "/eclipse/pools?size=1")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![fixtures::eclipse_pool_json()]))
+            .create();
+
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        let params = GetPoolsParams {
+            size: Some(1),
+            ..Default::default()
+        };
+        let pool = client
+            .get_pools(Chain::Eclipse.as_str(), params)
+            .await
+            .unwrap()
+            .into_first()
+            .unwrap();
+        assert_eq!(pool.address, fixtures::ECLIPSE_ETH_USDC_POOL);
+        assert_eq!(pool.token_a.symbol, "ETH");
+        assert!(pool.has_native_token(Chain::Eclipse));
+        assert_eq!(pool.token_mint_b, fixtures::ECLIPSE_USDC_MINT);
+        pool.validate().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_get_pools() {
+        let _m = mock("GET", "/solana/pools?")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "data": [],
+                    "meta": {
+                        "next": null,
+                        "previous": null
+                    }
+                }"#,
+            )
+            .create();
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        let params = GetPoolsParams::default();
+        let result = client.get_pools("solana", params).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_search_pools() {
+        let _m = mock("GET", "/solana/pools/search?q=sol")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "data": [],
+                    "meta": {
+                        "next": null,
+                        "previous": null
+                    }
+                }"#,
+            )
+            .create();
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        let params = SearchPoolsParams {
+            q: "sol",
+            ..Default::default()
+        };
+        let result = client.search_pools("solana", params).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_get_pool() {
+        let _m = mock(
+            "GET",
+            "/solana/pools/Czfq3xZZDmsdGdUyrNLtRhGc47cXcZtLG4crryfu44zE",
+        )
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+                "data": [],
+                "meta": {
+                    "next": null,
+                    "previous": null
+                }
+            }"#,
+        )
+        .create();
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        let result = client
+            .get_pool("solana", "Czfq3xZZDmsdGdUyrNLtRhGc47cXcZtLG4crryfu44zE")
+            .await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_empty_paginated_is_error() {
+        let _pool = mock("GET", format!("/solana/pools/{}", SOL_USDC_POOL).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![]))
+            .create();
+        let _token = mock("GET", format!("/solana/tokens/{}", USDC_MINT).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![]))
+            .create();
+
+        let lenient = OrcaClient::with_base_url(&mockito::server_url());
+        assert!(lenient
+            .get_pool("solana", SOL_USDC_POOL)
+            .await
+            .unwrap()
+            .data
+            .is_empty());
+        assert!(lenient
+            .get_token("solana", USDC_MINT)
+            .await
+            .unwrap()
+            .data
+            .is_empty());
+
+        let strict = OrcaClient::builder()
+            .with_base_url(&mockito::server_url())
+            .empty_paginated_is_error(true)
+            .build()
+            .unwrap();
+        match strict.get_pool("solana", SOL_USDC_POOL).await {
+            Err(OrcaError::NotFound { resource, id }) => {
+                assert_eq!(resource, "pool");
+                assert_eq!(id, SOL_USDC_POOL);
+            }
+            other => panic!(
+                "expected NotFound, got {:?}",
+                other.map(|page| page.data.len())
+            ),
+        }
+        assert!(matches!(
+            strict.get_token("solana", USDC_MINT).await,
+            Err(OrcaError::NotFound {
+                resource: "token",
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_pool_single() {
+        let _m = mock("GET", format!("/solana/pools/{}", SOL_USDC_POOL).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![fixtures::whirlpool_json()]))
+            .create();
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        let pool = client
+            .get_pool_single("solana", SOL_USDC_POOL)
+            .await
+            .unwrap();
+        assert_eq!(pool.address, SOL_USDC_POOL);
+    }
+
+    #[tokio::test]
+    async fn test_get_pool_single_empty() {
+        let _m = mock("GET", format!("/solana/pools/{}", SOL_USDC_POOL).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![]))
+            .create();
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        let result = client.get_pool_single("solana", SOL_USDC_POOL).await;
+        assert!(matches!(
+            result,
+            Err(OrcaError::NotFound { resource: "pool", id }) if id == SOL_USDC_POOL
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_token_single() {
+        let _m = mock("GET", format!("/solana/tokens/{}", USDC_MINT).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![fixtures::token_json(USDC_MINT)]))
+            .create();
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        let token = client.get_token_single("solana", USDC_MINT).await.unwrap();
+        assert_eq!(token.address, USDC_MINT);
+    }
+
+    #[tokio::test]
+    async fn test_get_token_single_empty() {
+        let _m = mock("GET", format!("/solana/tokens/{}", USDC_MINT).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![]))
+            .create();
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        let result = client.get_token_single("solana", USDC_MINT).await;
+        assert!(matches!(
+            result,
+            Err(OrcaError::NotFound { resource: "token", id }) if id == USDC_MINT
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_token_price() {
+        let _m = mock("GET", format!("/solana/tokens/{}", ORCA_MINT).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![fixtures::token_json(ORCA_MINT)]))
+            .create();
+        let _missing = mock("GET", format!("/solana/tokens/{}", WSOL_MINT).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![]))
+            .create();
+
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        assert_eq!(
+            client.get_token_price("solana", ORCA_MINT).await.unwrap(),
+            Decimal::from(130)
+        );
+        match client.get_token_price("solana", WSOL_MINT).await {
+            Err(OrcaError::NotFound { resource, id }) => {
+                assert_eq!(resource, "token");
+                assert_eq!(id, WSOL_MINT);
+            }
+            other => panic!("expected NotFound, got {:?}", other),
+        }
+
+        let unknown = mock("GET", format!("/devnet/tokens/{}", USDC_MINT).as_str())
+            .with_status(404)
+            .with_body("token not found")
+            .create();
+        assert!(matches!(
+            client.get_token_price("devnet", USDC_MINT).await,
+            Err(OrcaError::NotFound { resource: "token", id }) if id == USDC_MINT
+        ));
+        unknown.assert();
+    }
+
+    #[tokio::test]
+    async fn test_retries_failed_request() {
+        let _fail = mock("GET", "/solana/protocol/token/total_supply")
+            .with_status(503)
+            .expect(1)
+            .create();
+        let _ok = mock("GET", "/solana/protocol/token/total_supply")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"total_supply": "99999713"}"#)
+            .create();
+
+        let client = OrcaClient::builder()
+            .with_base_url(&mockito::server_url())
+            .with_retries(2, Duration::from_millis(1))
+            .build()
+            .unwrap();
+        let result = client.get_total_supply("solana").await;
+        assert_eq!(result.unwrap().total_supply, "99999713");
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_overrides_backoff() {
+        let _limited = mock("GET", "/eclipse/protocol/token/total_supply")
+            .with_status(429)
+            .with_header("retry-after", "0")
+            .with_body("slow down")
+            .expect(1)
+            .create();
+        let _ok = mock("GET", "/eclipse/protocol/token/total_supply")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"total_supply": "99999713"}"#)
+            .create();
+
+        let client = OrcaClient::builder()
+            .with_base_url(&mockito::server_url())
+            .with_retries(1, Duration::from_secs(60))
+            .build()
+            .unwrap();
+        let result =
+            tokio::time::timeout(Duration::from_secs(10), client.get_total_supply("eclipse"))
+                .await
+                .expect("the Retry-After delay should replace the 60s backoff");
+        assert_eq!(result.unwrap().total_supply, "99999713");
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_beyond_max_backoff() {
+        let limited = mock("GET", "/capped/solana/protocol/token/total_supply")
+            .with_status(429)
+            .with_header("retry-after", "3600")
+            .with_body("slow down")
+            .expect(1)
+            .create();
+
+        let client = OrcaClient::builder()
+            .with_base_url(&format!("{}/capped", mockito::server_url()))
+            .with_retry_policy(RetryPolicy {
+                max_retries: 3,
+                max_backoff: Duration::from_secs(1),
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+        let result =
+            tokio::time::timeout(Duration::from_secs(10), client.get_total_supply("solana"))
+                .await
+                .expect("a Retry-After beyond max_backoff should not be waited out");
+        assert!(matches!(result, Err(OrcaError::Api { status: 429, .. })));
+        limited.assert();
+    }
+
+    #[tokio::test]
+    async fn test_fallback_hosts() {
+        let primary = mock("GET", "/primary/v2/solana/protocol/token/total_supply")
+            .with_status(502)
+            .expect(1)
+            .create();
+        let backup = mock("GET", "/backup/v2/solana/protocol/token/total_supply")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"total_supply": "99999713"}"#)
+            .expect(2)
+            .create();
+        let missing = mock("GET", "/primary/v2/solana/protocol")
+            .with_status(404)
+            .expect(1)
+            .create();
+
+        // Port 1 refuses connections, so the first fallback fails at the connection level.
+        let client = OrcaClient::builder()
+            .with_host(&format!("{}/primary", mockito::server_url()))
+            .with_fallback_hosts(vec![
+                "http://127.0.0.1:1".to_string(),
+                format!("{}/backup", mockito::server_url()),
+            ])
+            .build()
+            .unwrap();
+        let supply = client.get_total_supply("solana").await.unwrap();
+        assert_eq!(supply.total_supply, "99999713");
+
+        let unreachable = OrcaClient::builder()
+            .with_host("http://127.0.0.1:1")
+            .with_fallback_hosts(vec![format!("{}/backup", mockito::server_url())])
+            .build()
+            .unwrap();
+        assert!(unreachable.get_total_supply("solana").await.is_ok());
+
+        assert!(matches!(
+            client.get_protocol_info("solana").await,
+            Err(OrcaError::Api { status: 404, .. })
+        ));
+        primary.assert();
+        backup.assert();
+        missing.assert();
+    }
+
+    #[tokio::test]
+    async fn test_fallback_hosts_return_last_error() {
+        let _primary = mock("GET", "/primary/v2/testnet/protocol")
+            .with_status(500)
+            .create();
+        let _backup = mock("GET", "/backup/v2/testnet/protocol")
+            .with_status(503)
+            .create();
+
+        let client = OrcaClient::builder()
+            .with_host(&format!("{}/primary", mockito::server_url()))
+            .with_fallback_hosts(vec![format!("{}/backup", mockito::server_url())])
+            .build()
+            .unwrap();
+        assert!(matches!(
+            client.get_protocol_info("testnet").await,
+            Err(OrcaError::Api { status: 503, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_fallback_hosts_with_normalized_base_url() {
+        let primary = mock("GET", "/normalized/v2/eclipse/protocol/token/total_supply")
+            .with_status(502)
+            .expect(1)
+            .create();
+        let backup = mock("GET", "/failover/v2/eclipse/protocol/token/total_supply")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"total_supply": "99999713"}"#)
+            .expect(1)
+            .create();
+
+        // `Url` lowercases the scheme and resolves the `.` segment, so request URLs no longer
+        // start with the base URL as written.
+        let server = mockito::server_url().replacen("http", "HTTP", 1);
+        let client = OrcaClient::builder()
+            .with_base_url(&format!("{server}/./normalized/v2"))
+            .with_fallback_hosts(vec![format!("{}/failover", mockito::server_url())])
+            .build()
+            .unwrap();
+        let supply = client.get_total_supply("eclipse").await.unwrap();
+        assert_eq!(supply.total_supply, "99999713");
+        primary.assert();
+        backup.assert();
+    }
+
+    #[test]
+    fn test_invalid_fallback_host_fails_build() {
+        for host in ["not a url", "mailto:dev@example.com"] {
+            let result = OrcaClient::builder()
+                .with_fallback_hosts(vec![host.to_string()])
+                .build();
+            assert!(matches!(result, Err(OrcaError::InvalidUrl(_))), "{host}");
+
+            let result = OrcaClient::builder().with_base_url(host).build();
+            assert!(matches!(result, Err(OrcaError::InvalidUrl(_))), "{host}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_html_error_page() {
+        let _m = mock("GET", "/devnet/protocol")
+            .with_status(200)
+            .with_header("content-type", "text/html; charset=utf-8")
+            .with_body("<!DOCTYPE html><html><body>502 Bad Gateway</body></html>")
+            .create();
+
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        match client.get_protocol_info("devnet").await {
+            Err(OrcaError::UnexpectedContentType {
+                content_type,
+                body_snippet,
+            }) => {
+                assert_eq!(content_type, "text/html; charset=utf-8");
+                assert!(body_snippet.contains("502 Bad Gateway"));
+            }
+            other => panic!("expected UnexpectedContentType, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_api_error_carries_retry_after() {
+        let _m = mock("GET", "/testnet/protocol")
+            .with_status(503)
+            .with_header("retry-after", "Thu, 01 Jan 1970 00:00:00 GMT")
+            .create();
+
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        let err = client.get_protocol_info("testnet").await.unwrap_err();
+        assert_eq!(err.retry_after(), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_retry_policy_backoff_curve() {
+        let policy = RetryPolicy {
+            max_retries: 5,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(1000),
+            multiplier: 3.0,
+            jitter: false,
+        };
+        let delays: Vec<Duration> = (0..5).map(|attempt| policy.backoff(attempt)).collect();
+        assert_eq!(
+            delays,
+            [100, 300, 900, 1000, 1000].map(Duration::from_millis)
+        );
+        assert_eq!(policy.backoff(u32::MAX), policy.max_backoff);
+
+        let jittered = RetryPolicy {
+            jitter: true,
+            ..policy
+        };
+        for attempt in 0..5 {
+            let delay = jittered.backoff(attempt);
+            assert!(delay >= policy.backoff(attempt) / 2 && delay <= policy.backoff(attempt));
+        }
+
+        // `with_retries` doubles the delay without a cap.
+        let doubling = OrcaClient::builder()
+            .with_retries(3, Duration::from_millis(100))
+            .retry_policy;
+        assert_eq!(doubling.backoff(10), Duration::from_millis(102_400));
+    }
+
+    #[tokio::test]
+    async fn test_with_proxy_routes_requests() {
+        // A proxied request carries the absolute target URL in its request line.
+        let m = mock(
+            "GET",
+            Matcher::Regex("^http://orca.invalid/v2/eclipse/protocol$".to_string()),
+        )
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            serde_json::json!({
+                "fees24hUsdc": "1",
+                "revenue24hUsdc": "2",
+                "tvl": "3",
+                "volume24hUsdc": "4"
+            })
+            .to_string(),
+        )
+        .expect(1)
+        .create();
+
+        let client = OrcaClient::builder()
+            .with_base_url("http://orca.invalid/v2")
+            .with_proxy(Proxy::http(mockito::server_url()).unwrap())
+            .build()
+            .unwrap();
+        assert_eq!(client.get_protocol_info("eclipse").await.unwrap().tvl, "3");
+        m.assert();
+    }
+
+    #[tokio::test]
+    async fn test_connection_pool_tuning() {
+        let m = mock("GET", "/testnet/protocol/token/circulating_supply")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"circulating_supply": "74999702"}"#)
+            .expect(2)
+            .create();
+
+        let client = OrcaClient::builder()
+            .with_base_url(&mockito::server_url())
+            .with_pool_max_idle_per_host(1)
+            .with_tcp_keepalive(Duration::from_secs(30))
+            .build()
+            .unwrap();
+        for _ in 0..2 {
+            let supply = client.get_circulating_supply("testnet").await.unwrap();
+            assert_eq!(supply.circulating_supply, "74999702");
+        }
+        m.assert();
+    }
+
+    #[tokio::test]
+    async fn test_no_retry_surfaces_first_error() {
+        let fail = mock("GET", "/solana/protocol/token/total_supply")
+            .with_status(503)
+            .expect(1)
+            .create();
+        let ok = mock("GET", "/solana/protocol/token/total_supply")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"total_supply": "99999713"}"#)
+            .expect(0)
+            .create();
+
+        let client = OrcaClient::builder()
+            .with_base_url(&mockito::server_url())
+            .with_retries(2, Duration::from_millis(1))
+            .build()
+            .unwrap();
+        let result = client
+            .with_options(RequestOptions { no_retry: true })
+            .get_total_supply("solana")
+            .await;
+        assert!(matches!(result, Err(OrcaError::Api { status: 503, .. })));
+        fail.assert();
+        ok.assert();
+    }
+
+    #[tokio::test]
+    async fn test_get_top_yield_pools() {
+        let _m = mock("GET", "/solana/pools")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("sortBy".into(), "yieldOverTvl".into()),
+                Matcher::UrlEncoded("sortDirection".into(), "desc".into()),
+                Matcher::UrlEncoded("minTvl".into(), "50000".into()),
+                Matcher::UrlEncoded("size".into(), "1".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![
+                fixtures::whirlpool_json(),
+                fixtures::whirlpool_json(),
+            ]))
+            .create();
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        let pools = client
+            .get_top_yield_pools("solana", 50000.0, 1)
+            .await
+            .unwrap();
+        assert_eq!(pools.len(), 1);
+        assert_eq!(pools[0].address, SOL_USDC_POOL);
+    }
+
+    #[tokio::test]
+    async fn test_get_safe_pools() {
+        let verified = serde_json::json!(["verified"]).to_string();
+        let safe = fixtures::merge(
+            fixtures::whirlpool_json(),
+            serde_json::json!({
+                "address": USDC_MINT,
+                "tokenA": { "tags": verified },
+                "tokenB": { "tags": verified }
+            }),
+        );
+        let _m = mock("GET", "/solana/pools?hasWarning=false&size=10")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![fixtures::whirlpool_json(), safe]))
+            .create();
+
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        let params = GetPoolsParams {
+            size: Some(10),
+            ..Default::default()
+        };
+        let pools = client.get_safe_pools("solana", params).await.unwrap();
+        assert_eq!(pools.data.len(), 1);
+        assert_eq!(pools.data[0].address, USDC_MINT);
+    }
+
+    #[tokio::test]
+    async fn test_pools_stream_follows_cursor() {
+        let _first = mock("GET", "/solana/pools")
+            .match_query(Matcher::Missing)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "data": [fixtures::whirlpool_json()],
+                    "meta": { "next": "page-2", "previous": null }
+                })
+                .to_string(),
+            )
+            .create();
+        let _second = mock("GET", "/solana/pools?next=page-2")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![fixtures::whirlpool_json()]))
+            .create();
+
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        let pools: Vec<Whirlpool> = client
+            .pools_stream("solana", GetPoolsParams::default())
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(pools.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_pools_stream_stops_when_dropped() {
+        let other = fixtures::merge(
+            fixtures::whirlpool_json(),
+            serde_json::json!({ "address": "HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ" }),
+        );
+        let first = mock("GET", "/solana/pools")
+            .match_query(Matcher::Missing)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "data": [fixtures::whirlpool_json(), other],
+                    "meta": { "next": "page-2", "previous": null }
+                })
+                .to_string(),
+            )
+            .expect(1)
+            .create();
+        let second = mock("GET", "/solana/pools?next=page-2")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![fixtures::whirlpool_json()]))
+            .expect(0)
+            .create();
+
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        {
+            let stream = client.pools_stream("solana", GetPoolsParams::default());
+            futures::pin_mut!(stream);
+            let pool = stream.next().await.unwrap().unwrap();
+            assert_eq!(pool.address, SOL_USDC_POOL);
+            let pool = stream.next().await.unwrap().unwrap();
+            assert_ne!(pool.address, SOL_USDC_POOL);
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        first.assert();
+        second.assert();
+    }
+
+    #[tokio::test]
+    async fn test_pools_stream_drop_aborts_in_flight_request() {
+        use tokio::io::AsyncReadExt;
+
+        // A server that accepts the connection but never answers.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 4096];
+            while socket.read(&mut buf).await.unwrap_or(0) > 0 {}
+        });
+
+        let client = OrcaClient::with_base_url(&base_url);
+        {
+            let stream = client.pools_stream("solana", GetPoolsParams::default());
+            futures::pin_mut!(stream);
+            let pending = tokio::time::timeout(Duration::from_millis(100), stream.next()).await;
+            assert!(pending.is_err());
+        }
+        // Dropping the stream closes the connection, which ends the server's read loop.
+        tokio::time::timeout(Duration::from_secs(5), server)
+            .await
+            .expect("in-flight request was not aborted")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_pools_stream_with_cancel() {
+        use tokio::io::AsyncReadExt;
+
+        // A server that accepts the connection but never answers.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 4096];
+            while socket.read(&mut buf).await.unwrap_or(0) > 0 {}
+        });
+
+        let client = OrcaClient::with_base_url(&base_url);
+        let cancel = CancellationToken::new();
+        let stream =
+            client.pools_stream_with_cancel("solana", GetPoolsParams::default(), cancel.clone());
+        futures::pin_mut!(stream);
+        let trigger = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            cancel.cancel();
+        });
+        let ended = tokio::time::timeout(Duration::from_secs(5), stream.next())
+            .await
+            .expect("stream did not end on cancellation");
+        assert!(ended.is_none());
+        trigger.await.unwrap();
+
+        // The stream is still alive, but its in-flight request has already been dropped.
+        tokio::time::timeout(Duration::from_secs(5), server)
+            .await
+            .expect("in-flight request was not aborted")
+            .unwrap();
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_valid_pools_stream_filters_invalid() {
+        let invalid = fixtures::merge(
+            fixtures::whirlpool_json(),
+            serde_json::json!({
+                "address": "HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ",
+                "tickSpacing": 0
+            }),
+        );
+        let _m = mock("GET", "/solana/pools")
+            .match_query(Matcher::Missing)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![
+                invalid,
+                fixtures::whirlpool_json(),
+            ]))
+            .create();
+
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        let pools: Vec<Whirlpool> = client
+            .valid_pools_stream("solana", GetPoolsParams::default())
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(pools.len(), 1);
+        assert_eq!(pools[0].address, SOL_USDC_POOL);
+    }
+
+    /// Log output captured from a `tracing` subscriber.
+    #[cfg(feature = "tracing")]
+    #[derive(Clone, Default)]
+    struct Captured(Arc<std::sync::Mutex<Vec<u8>>>);
+
+    #[cfg(feature = "tracing")]
+    impl std::io::Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    impl Captured {
+        /// Captures events at `DEBUG` and above on this thread until the guard is dropped.
+        fn install() -> (Captured, tracing::subscriber::DefaultGuard) {
+            let captured = Captured::default();
+            let writer = captured.clone();
+            let subscriber = tracing_subscriber::fmt()
+                .with_max_level(tracing::Level::DEBUG)
+                .with_ansi(false)
+                .with_writer(move || writer.clone())
+                .finish();
+            (captured, tracing::subscriber::set_default(subscriber))
+        }
+
+        fn logs(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    #[tokio::test]
+    async fn test_logs_redacted_query_params() {
+        let (captured, _guard) = Captured::install();
+
+        let _m = mock("GET", "/solana/pools")
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![]))
+            .create();
+        let client = OrcaClient::builder()
+            .with_base_url(&mockito::server_url())
+            .with_redacted_query_params(&["minTvl"])
+            .build()
+            .unwrap();
+        let params = GetPoolsParams {
+            has_rewards: Some(true),
+            min_tvl: Some(1000.0),
+            size: Some(10),
+            ..Default::default()
+        };
+        client.get_pools("solana", params).await.unwrap();
+
+        let logs = captured.logs();
+        assert!(logs.contains("endpoint=\"/solana/pools\""), "{logs}");
+        assert!(logs.contains("hasRewards=true"), "{logs}");
+        assert!(logs.contains("size=10"), "{logs}");
+        assert!(logs.contains("minTvl=[REDACTED]"), "{logs}");
+        assert!(!logs.contains("1000"), "{logs}");
+
+        let url = Url::parse("https://api.orca.so/v2/solana/pools?apiKey=hunter2&size=1").unwrap();
+        assert_eq!(client.redacted_query(&url), "apiKey=[REDACTED]&size=1");
+    }
+
+    #[cfg(feature = "tracing")]
+    #[tokio::test]
+    async fn test_logs_slow_requests() {
+        let _m = mock("GET", "/testnet/protocol/token/total_supply")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"total_supply": "99999713"}"#)
+            .create();
+
+        let (captured, _guard) = Captured::install();
+        let relaxed = OrcaClient::builder()
+            .with_base_url(&mockito::server_url())
+            .with_slow_request_threshold(Duration::from_secs(3600))
+            .build()
+            .unwrap();
+        relaxed.get_total_supply("testnet").await.unwrap();
+        assert!(!captured.logs().contains("slow request"));
+
+        let strict = OrcaClient::builder()
+            .with_base_url(&mockito::server_url())
+            .with_slow_request_threshold(Duration::from_nanos(1))
+            .build()
+            .unwrap();
+        strict.get_total_supply("testnet").await.unwrap();
+        let logs = captured.logs();
+        assert!(logs.contains("WARN"), "{logs}");
+        assert!(logs.contains("slow request"), "{logs}");
+        assert!(
+            logs.contains("endpoint=\"/testnet/protocol/token/total_supply\""),
+            "{logs}"
+        );
+        assert!(logs.contains("threshold_ms=0"), "{logs}");
+    }
+
+    /// A `log` logger that keeps this crate's records, for tests of the `debug` feature.
+    #[cfg(feature = "debug")]
+    struct CapturedLog(std::sync::Mutex<Vec<String>>);
+
+    #[cfg(feature = "debug")]
+    impl log::Log for CapturedLog {
+        fn enabled(&self, metadata: &log::Metadata) -> bool {
+            metadata
+                .target()
+                .starts_with(module_path!().split("::").next().unwrap())
+        }
+
+        fn log(&self, record: &log::Record) {
+            if !self.enabled(record.metadata()) {
+                return;
+            }
+            let line = format!("{} {}", record.level(), record.args());
+            self.0.lock().unwrap().push(line);
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[cfg(feature = "debug")]
+    #[tokio::test]
+    async fn test_logs_slow_requests_with_log() {
+        static LOGGER: CapturedLog = CapturedLog(std::sync::Mutex::new(Vec::new()));
+        // The logger is global; each client below uses its own path prefix so records from
+        // other tests cannot match.
+        let _ = log::set_logger(&LOGGER);
+        log::set_max_level(log::LevelFilter::Trace);
+        let relaxed_mock = mock("GET", "/relaxed/solana/protocol/token/total_supply")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"total_supply": "99999713"}"#)
+            .create();
+        let strict_mock = mock("GET", "/strict/solana/protocol/token/total_supply")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"total_supply": "99999713"}"#)
+            .create();
+
+        let relaxed = OrcaClient::builder()
+            .with_base_url(&format!("{}/relaxed", mockito::server_url()))
+            .with_slow_request_threshold(Duration::from_secs(3600))
+            .build()
+            .unwrap();
+        relaxed.get_total_supply("solana").await.unwrap();
+        let strict = OrcaClient::builder()
+            .with_base_url(&format!("{}/strict", mockito::server_url()))
+            .with_slow_request_threshold(Duration::from_nanos(1))
+            .build()
+            .unwrap();
+        strict.get_total_supply("solana").await.unwrap();
+        relaxed_mock.assert();
+        strict_mock.assert();
+
+        let records = LOGGER.0.lock().unwrap().clone();
+        assert!(!records.iter().any(|record| record.contains("/relaxed/")));
+        assert!(
+            records.iter().any(|record| record
+                .starts_with("WARN slow request to /strict/solana/protocol/token/total_supply")),
+            "{records:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_watch_pool_yields_snapshots() {
+        let m = mock("GET", format!("/solana/pools/{}", SOL_USDC_POOL).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![fixtures::whirlpool_json()]))
+            .expect(2)
+            .create();
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        let snapshots: Vec<_> = client
+            .watch_pool("solana", SOL_USDC_POOL, Duration::from_millis(10))
+            .take(2)
+            .collect()
+            .await;
+        assert_eq!(snapshots.len(), 2);
+        assert!(snapshots.iter().all(|pool| pool.is_ok()));
+        m.assert();
+    }
+
+    #[tokio::test]
+    async fn test_get_protocol_info_all() {
+        let _solana = mock("GET", "/solana/protocol")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "fees24hUsdc": "317428.0521046",
+                    "revenue24hUsdc": "41265.646773",
+                    "tvl": "230551269.0085",
+                    "volume24hUsdc": "552567794.7830"
                 }"#,
             )
             .create();
+        let _eclipse = mock("GET", "/eclipse/protocol").with_status(503).create();
+
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        for concurrency in [None, Some(1)] {
+            let results = client.get_protocol_info_all(&Chain::ALL, concurrency).await;
+            assert_eq!(results.len(), 2);
+            assert_eq!(results[0].0, Chain::Solana);
+            assert_eq!(results[0].1.as_ref().unwrap().tvl, "230551269.0085");
+            assert_eq!(results[1].0, Chain::Eclipse);
+            assert!(matches!(
+                results[1].1,
+                Err(OrcaError::Api { status: 503, .. })
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_pools_exist() {
+        let missing = "HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ";
+        let broken = "83v8iPyZihDEjDdY8RdZddyZNyUtXngz69Lgo9Kt5d6d";
+        let _found = mock("GET", format!("/solana/pools/{}", SOL_USDC_POOL).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![fixtures::whirlpool_json()]))
+            .create();
+        let _missing = mock("GET", format!("/solana/pools/{}", missing).as_str())
+            .with_status(404)
+            .create();
+        let _broken = mock("GET", format!("/solana/pools/{}", broken).as_str())
+            .with_status(500)
+            .create();
+
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        let results = client
+            .check_pools_exist("solana", &[SOL_USDC_POOL, missing, broken], 2)
+            .await;
+        assert_eq!(results.len(), 3);
+        assert!(matches!(results[SOL_USDC_POOL], Ok(true)));
+        assert!(matches!(results[missing], Ok(false)));
+        assert!(matches!(
+            results[broken],
+            Err(OrcaError::Api { status: 500, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_pools_concurrent() {
+        let missing = "HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ";
+        let broken = "83v8iPyZihDEjDdY8RdZddyZNyUtXngz69Lgo9Kt5d6d";
+        let _found = mock("GET", format!("/solana/pools/{}", SOL_USDC_POOL).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![fixtures::whirlpool_json()]))
+            .create();
+        let _missing = mock("GET", format!("/solana/pools/{}", missing).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![]))
+            .create();
+        let _broken = mock("GET", format!("/solana/pools/{}", broken).as_str())
+            .with_status(500)
+            .create();
+
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        let results = client
+            .get_pools_concurrent("solana", &[broken, SOL_USDC_POOL, missing], 2)
+            .await;
+        assert_eq!(results.len(), 3);
+        assert!(matches!(
+            results[0],
+            Err(OrcaError::Api { status: 500, .. })
+        ));
+        assert_eq!(results[1].as_ref().unwrap().address, SOL_USDC_POOL);
+        assert!(matches!(&results[2], Err(OrcaError::NotFound { id, .. }) if id == missing));
+    }
+
+    #[tokio::test]
+    async fn test_get_tvl_histogram() {
+        let pool = |tvl: &str| {
+            fixtures::merge(
+                fixtures::whirlpool_json(),
+                serde_json::json!({ "tvlUsdc": tvl }),
+            )
+        };
+        let _first = mock("GET", "/solana/pools")
+            .match_query(Matcher::Missing)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "data": [pool("50"), pool("5000")],
+                    "meta": { "next": "page-2", "previous": null }
+                })
+                .to_string(),
+            )
+            .create();
+        let _second = mock("GET", "/solana/pools?next=page-2")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![pool("7000"), pool("1")]))
+            .create();
+
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        let histogram = client
+            .get_tvl_histogram(
+                "solana",
+                GetPoolsParams::default(),
+                &[Decimal::from(10), Decimal::from(1000)],
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            histogram,
+            vec![(Decimal::from(10), 1), (Decimal::from(1000), 2)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rank_lp_opportunities() {
+        let pool = |address: &str, mint_a: &str, tvl: &str, fees: &str| {
+            fixtures::merge(
+                fixtures::whirlpool_json(),
+                serde_json::json!({
+                    "address": address,
+                    "tokenMintA": mint_a,
+                    "tvlUsdc": tvl,
+                    "stats": { "24h": { "fees": fees, "rewards": "0" } }
+                }),
+            )
+        };
+        let deep = "HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ";
+        let shallow = "83v8iPyZihDEjDdY8RdZddyZNyUtXngz69Lgo9Kt5d6d";
+        let unrelated = "7qbRF6YsyGuLUVs6Y1q64bdVrfe4ZcUUz1JRdoVNUJnm";
+        let m = mock("GET", "/solana/pools")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("token".into(), WSOL_MINT.into()),
+                Matcher::UrlEncoded("stats".into(), "24h".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![
+                // 10% APR on a tiny pool: heavily penalized.
+                pool(shallow, WSOL_MINT, "1000", "0.27397"),
+                pool(SOL_USDC_POOL, WSOL_MINT, "100000000", "120000"),
+                pool(deep, WSOL_MINT, "1000000", "10000"),
+                pool(unrelated, ORCA_MINT, "1000000", "50000"),
+            ]))
+            .create();
 
         let client = OrcaClient::with_base_url(&mockito::server_url());
-        let result = client.get_token_info("solana").await;
+        let ranked = client
+            .rank_lp_opportunities("solana", WSOL_MINT)
+            .await
+            .unwrap();
+        let order: Vec<_> = ranked
+            .iter()
+            .map(|(pool, _)| pool.address.as_str())
+            .collect();
+        assert_eq!(order, vec![deep, SOL_USDC_POOL, shallow]);
+        m.assert();
+        for (pool, score) in &ranked {
+            assert_eq!(*score, pool.lp_opportunity_score().unwrap());
+        }
+        assert!(ranked.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+    }
 
-        assert!(result.is_ok());
-        let token_info = result.unwrap();
-        assert_eq!(token_info.name, "Orca");
+    #[tokio::test]
+    async fn test_get_pools_by_addresses() {
+        let other = "HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ";
+        let _first = mock("GET", "/solana/pools")
+            .match_query(Matcher::Exact(format!(
+                "addresses={}&addresses={}",
+                SOL_USDC_POOL, other
+            )))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!({
+                    "data": [fixtures::whirlpool_json()],
+                    "meta": { "next": "page-2", "previous": null }
+                })
+                .to_string(),
+            )
+            .create();
+        let _second = mock("GET", "/solana/pools")
+            .match_query(Matcher::Exact(format!(
+                "next=page-2&addresses={}&addresses={}",
+                SOL_USDC_POOL, other
+            )))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![fixtures::merge(
+                fixtures::whirlpool_json(),
+                serde_json::json!({ "address": other }),
+            )]))
+            .create();
+
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        let pools = client
+            .get_pools_by_addresses("solana", &[SOL_USDC_POOL, other])
+            .await
+            .unwrap();
+        let addresses: Vec<&str> = pools.iter().map(|p| p.address.as_str()).collect();
+        assert_eq!(addresses, vec![SOL_USDC_POOL, other]);
     }
 
     #[tokio::test]
-    async fn test_get_circulating_supply() {
-        let _m = mock("GET", "/solana/protocol/token/circulating_supply")
+    async fn test_sum_pool_fees() {
+        let other = "HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ";
+        let _m = mock("GET", "/solana/pools")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("addresses".into(), other.into()),
+                Matcher::UrlEncoded("stats".into(), "24h".into()),
+            ]))
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(r#"{"circulating_supply": "53275183"}"#)
+            .with_body(fixtures::paginated(vec![
+                fixtures::whirlpool_json(),
+                fixtures::merge(
+                    fixtures::whirlpool_json(),
+                    serde_json::json!({
+                        "address": other,
+                        "stats": { "24h": { "fees": "99.5" } }
+                    }),
+                ),
+            ]))
             .create();
 
         let client = OrcaClient::with_base_url(&mockito::server_url());
-        let result = client.get_circulating_supply("solana").await;
+        let total = client
+            .sum_pool_fees("solana", &[SOL_USDC_POOL, other], TimePeriod::H24)
+            .await
+            .unwrap();
+        assert_eq!(total, Decimal::new(1201000, 1));
+    }
 
-        assert!(result.is_ok());
-        let circulating_supply = result.unwrap();
-        assert_eq!(circulating_supply.circulating_supply, "53275183");
+    #[tokio::test]
+    async fn test_get_tokens_by_mints() {
+        let _m = mock("GET", "/solana/tokens")
+            .match_query(Matcher::UrlEncoded(
+                "tokens".into(),
+                format!("{},{}", USDC_MINT, WSOL_MINT),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![
+                fixtures::token_json(USDC_MINT),
+                fixtures::token_json(WSOL_MINT),
+            ]))
+            .create();
+
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        let tokens = client
+            .get_tokens_by_mints("solana", &[USDC_MINT, WSOL_MINT])
+            .await
+            .unwrap();
+        assert_eq!(tokens.len(), 2);
+        assert!(tokens[1].is_native_sol());
     }
 
     #[tokio::test]
-    async fn test_get_total_supply() {
-        let _m = mock("GET", "/solana/protocol/token/total_supply")
+    async fn test_cache_serves_repeated_requests() {
+        let m = mock("GET", "/solana/protocol/token/total_supply")
             .with_status(200)
             .with_header("content-type", "application/json")
             .with_body(r#"{"total_supply": "99999713"}"#)
+            .expect(1)
             .create();
 
-        let client = OrcaClient::with_base_url(&mockito::server_url());
-        let result = client.get_total_supply("solana").await;
+        let client = OrcaClient::builder()
+            .with_base_url(&mockito::server_url())
+            .with_cache(Duration::from_secs(60))
+            .build()
+            .unwrap();
+        for _ in 0..3 {
+            let supply = client.get_total_supply("solana").await.unwrap();
+            assert_eq!(supply.total_supply, "99999713");
+        }
+        m.assert();
 
-        assert!(result.is_ok());
-        let total_supply = result.unwrap();
-        assert_eq!(total_supply.total_supply, "99999713");
+        // A fresh client has its own, empty cache.
+        let fresh = OrcaClient::builder()
+            .with_base_url(&mockito::server_url())
+            .with_cache(Duration::from_secs(60))
+            .build()
+            .unwrap();
+        assert!(fresh.get_total_supply("solana").await.is_ok());
     }
 
     #[tokio::test]
-    async fn test_get_tokens() {
-        let _m = mock("GET", "/solana/tokens?size=1")
+    async fn test_cache_entries_expire() {
+        let m = mock("GET", "/solana/protocol/token/total_supply")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(
-                r#"{
-                    "data": [
-                        {
-                            "address": "So11111111111111111111111111111111111111112",
-                            "decimals": 9,
-                            "extensions": "{}",
-                            "freezeAuthority": null,
-                            "isInitialized": true,
-                            "metadata": "{}",
-                            "mintAuthority": null,
-                            "priceUsdc": "130.0",
-                            "stats": "{}",
-                            "supply": "1000000000",
-                            "tags": "[]",
-                            "tokenProgram": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
-                            "updatedAt": "2025-05-09T00:04:50.745163Z",
-                            "updatedEpoch": 784
-                        }
-                    ],
-                    "meta": {
-                        "next": "some-next-cursor",
-                        "previous": null
-                    }
-                }"#,
-            )
+            .with_body(r#"{"total_supply": "99999713"}"#)
+            .expect(2)
             .create();
 
-        let client = OrcaClient::with_base_url(&mockito::server_url());
-        let result = client
-            .get_tokens("solana", None, None, Some(1), None, None, None)
-            .await;
-        assert!(result.is_ok());
-        let tokens = result.unwrap();
-        assert_eq!(tokens.data.len(), 1);
-        assert_eq!(
-            tokens.data[0].address,
-            "So11111111111111111111111111111111111111112"
-        );
+        let client = OrcaClient::builder()
+            .with_base_url(&mockito::server_url())
+            .with_cache(Duration::from_millis(20))
+            .build()
+            .unwrap();
+        client.get_total_supply("solana").await.unwrap();
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        client.get_total_supply("solana").await.unwrap();
+        m.assert();
     }
 
     #[tokio::test]
-    async fn test_search_tokens() {
-        let _m = mock("GET", "/solana/tokens/search?q=sol")
+    async fn test_rate_limit_spaces_requests() {
+        let m = mock("GET", "/solana/protocol/token/total_supply")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"total_supply": "99999713"}"#)
+            .expect(4)
+            .create();
+
+        let client = OrcaClient::builder()
+            .with_base_url(&mockito::server_url())
+            .with_rate_limit(20)
+            .build()
+            .unwrap();
+        let started = std::time::Instant::now();
+        for _ in 0..4 {
+            client.get_total_supply("solana").await.unwrap();
+        }
+        // The first request goes out immediately; the other three wait 50ms each.
+        assert!(started.elapsed() >= Duration::from_millis(150));
+        m.assert();
+
+        let result = OrcaClient::builder().with_rate_limit(0).build();
+        assert!(matches!(result, Err(OrcaError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_get_all_pools_dedupes_across_pages() {
+        let other = "HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ";
+        let _first = mock("GET", "/solana/pools")
+            .match_query(Matcher::Missing)
             .with_status(200)
             .with_header("content-type", "application/json")
             .with_body(
-                r#"{
-                    "data": [],
-                    "meta": {
-                        "next": null,
-                        "previous": null
-                    }
-                }"#,
+                serde_json::json!({
+                    "data": [fixtures::whirlpool_json()],
+                    "meta": { "next": "page-2", "previous": null }
+                })
+                .to_string(),
             )
             .create();
+        // The first pool shifted onto the second page while the scan was in progress.
+        let _second = mock("GET", "/solana/pools?next=page-2")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![
+                fixtures::whirlpool_json(),
+                fixtures::merge(
+                    fixtures::whirlpool_json(),
+                    serde_json::json!({ "address": other }),
+                ),
+            ]))
+            .create();
 
         let client = OrcaClient::with_base_url(&mockito::server_url());
-        let result = client.search_tokens("solana", "sol").await;
-        assert!(result.is_ok());
+        let pools = client
+            .get_all_pools("solana", GetPoolsParams::default())
+            .await
+            .unwrap();
+        let addresses: Vec<&str> = pools.iter().map(|p| p.address.as_str()).collect();
+        assert_eq!(addresses, vec![SOL_USDC_POOL, other]);
     }
 
     #[tokio::test]
-    async fn test_get_token() {
-        let _m = mock(
-            "GET",
-            "/solana/tokens/So11111111111111111111111111111111111111112",
-        )
-        .with_status(200)
-        .with_header("content-type", "application/json")
-        .with_body(
-            r#"{
-                "data": [],
-                "meta": {
-                    "next": null,
-                    "previous": null
-                }
-            }"#,
-        )
-        .create();
+    async fn test_search() {
+        let tokens = mock("GET", "/eclipse/tokens/search?q=orca&size=1")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![
+                fixtures::token_json(ORCA_MINT),
+                fixtures::token_json(USDC_MINT),
+            ]))
+            .expect(1)
+            .create();
+        let pools = mock("GET", "/eclipse/pools/search?q=orca&size=1")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![fixtures::whirlpool_json()]))
+            .expect(1)
+            .create();
 
         let client = OrcaClient::with_base_url(&mockito::server_url());
-        let result = client
-            .get_token("solana", "So11111111111111111111111111111111111111112")
-            .await;
-        assert!(result.is_ok());
+        let results = client.search("eclipse", "orca", 1).await.unwrap();
+        tokens.assert();
+        pools.assert();
+        assert_eq!(results.tokens.len(), 1);
+        assert_eq!(results.tokens[0].address, ORCA_MINT);
+        assert_eq!(results.pools.len(), 1);
+        assert!(!results.is_empty());
+
+        assert!(matches!(
+            client.search("eclipse", "orca", 0).await,
+            Err(OrcaError::Validation(_))
+        ));
     }
 
     #[tokio::test]
-    async fn test_get_lock_info() {
-        let _m = mock(
+    async fn test_default_pool_params() {
+        let defaults = mock(
             "GET",
-            "/solana/lock/Czfq3xZZDmsdGdUyrNLtRhGc47cXcZtLG4crryfu44zE",
+            "/eclipse/pools?sortBy=tvl&size=50&includeBlocked=false",
         )
         .with_status(200)
         .with_header("content-type", "application/json")
-        .with_body(
-            r#"[
-                {
-                    "lockedPercentage": "0.7",
-                    "name": "Whirlpool-Lock"
-                }
-            ]"#,
+        .with_body(fixtures::paginated(vec![]))
+        .expect(1)
+        .create();
+        let overridden = mock(
+            "GET",
+            "/eclipse/pools?sortBy=volume&size=50&includeBlocked=true",
         )
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(fixtures::paginated(vec![]))
+        .expect(1)
         .create();
 
+        let client = OrcaClient::builder()
+            .with_base_url(&mockito::server_url())
+            .with_default_pool_params(GetPoolsParams {
+                sort_by: Some("tvl"),
+                size: Some(50),
+                include_blocked: Some(false),
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+        client
+            .get_pools("eclipse", GetPoolsParams::default())
+            .await
+            .unwrap();
+        let params = GetPoolsParams {
+            sort_by: Some("volume"),
+            include_blocked: Some(true),
+            ..Default::default()
+        };
+        client.get_pools("eclipse", params).await.unwrap();
+        defaults.assert();
+        overridden.assert();
+
+        let result = OrcaClient::builder()
+            .with_default_pool_params(GetPoolsParams {
+                token: Some(&["So11111111111111111111111111111111111111112"]),
+                ..Default::default()
+            })
+            .build();
+        assert!(matches!(result, Err(OrcaError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_get_pool_map() {
+        let other = "HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ";
+        let page = |pool: serde_json::Value, next: &str| {
+            serde_json::json!({ "data": [pool], "meta": { "next": next, "previous": null } })
+                .to_string()
+        };
+        let _first = mock("GET", "/testnet/pools")
+            .match_query(Matcher::Missing)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(page(fixtures::whirlpool_json(), "page-2"))
+            .create();
+        let second = mock("GET", "/testnet/pools?next=page-2")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(page(
+                fixtures::merge(
+                    fixtures::whirlpool_json(),
+                    serde_json::json!({ "address": other }),
+                ),
+                "page-3",
+            ))
+            .expect(1)
+            .create();
+
         let client = OrcaClient::with_base_url(&mockito::server_url());
-        let result = client
-            .get_lock_info(
-                "solana",
-                "Czfq3xZZDmsdGdUyrNLtRhGc47cXcZtLG4crryfu44zE",
-            )
-            .await;
-        assert!(result.is_ok());
-        let lock_info = result.unwrap();
-        assert_eq!(lock_info.len(), 1);
-        assert_eq!(lock_info[0].name, "Whirlpool-Lock");
+        let (pools, pending) = client
+            .get_pool_map("testnet", GetPoolsParams::default(), 2)
+            .await
+            .unwrap();
+        second.assert();
+        assert_eq!(pending, Some(Cursor::from("page-3")));
+        assert_eq!(pools.len(), 2);
+        assert_eq!(pools[SOL_USDC_POOL].address, SOL_USDC_POOL);
+        assert_eq!(pools[other].address, other);
+
+        assert!(matches!(
+            client
+                .get_pool_map("testnet", GetPoolsParams::default(), 0)
+                .await,
+            Err(OrcaError::Validation(_))
+        ));
     }
 
     #[tokio::test]
-    async fn test_get_pools() {
-        let _m = mock("GET", "/solana/pools?")
+    async fn test_get_pool_addresses() {
+        let other = "HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ";
+        let _first = mock("GET", "/eclipse/pools?sortBy=tvl")
             .with_status(200)
             .with_header("content-type", "application/json")
             .with_body(
-                r#"{
-                    "data": [],
-                    "meta": {
-                        "next": null,
-                        "previous": null
-                    }
-                }"#,
+                serde_json::json!({
+                    "data": [fixtures::whirlpool_json()],
+                    "meta": { "next": "page-2", "previous": null }
+                })
+                .to_string(),
             )
             .create();
+        // Only `address` is read, so pools missing other fields still come through.
+        let _second = mock("GET", "/eclipse/pools?sortBy=tvl&next=page-2")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![
+                fixtures::whirlpool_json(),
+                serde_json::json!({ "address": other }),
+            ]))
+            .create();
+
         let client = OrcaClient::with_base_url(&mockito::server_url());
-        let params = GetPoolsParams::default();
-        let result = client.get_pools("solana", params).await;
-        assert!(result.is_ok());
+        let params = GetPoolsParams {
+            sort_by: Some("tvl"),
+            ..Default::default()
+        };
+        let addresses = client.get_pool_addresses("eclipse", params).await.unwrap();
+        assert_eq!(addresses, vec![SOL_USDC_POOL, other]);
     }
 
     #[tokio::test]
-    async fn test_search_pools() {
-        let _m = mock("GET", "/solana/pools/search?q=sol")
+    async fn test_find_pools_with_token() {
+        let orca_pool = "2p7nYbtPBgtmY69NsE8DAW6szpRJn7tQvDnqvoEWQvjY";
+        let _m = mock("GET", format!("/solana/pools?token={}", ORCA_MINT).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![
+                fixtures::merge(
+                    fixtures::whirlpool_json(),
+                    serde_json::json!({ "address": orca_pool, "tokenMintB": ORCA_MINT }),
+                ),
+                fixtures::whirlpool_json(),
+            ]))
+            .create();
+
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        let pools = client
+            .find_pools_with_token("solana", ORCA_MINT)
+            .await
+            .unwrap();
+        assert_eq!(pools.len(), 1);
+        assert_eq!(pools[0].address, orca_pool);
+    }
+
+    #[tokio::test]
+    async fn test_best_pools_for_token() {
+        let deep = "2p7nYbtPBgtmY69NsE8DAW6szpRJn7tQvDnqvoEWQvjY";
+        let _m = mock("GET", "/eclipse/pools")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("token".into(), ORCA_MINT.into()),
+                Matcher::UrlEncoded("sortBy".into(), "tvl".into()),
+                Matcher::UrlEncoded("sortDirection".into(), "desc".into()),
+                Matcher::UrlEncoded("size".into(), "2".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![
+                fixtures::merge(
+                    fixtures::whirlpool_json(),
+                    serde_json::json!({ "address": deep, "tokenMintA": ORCA_MINT }),
+                ),
+                fixtures::whirlpool_json(),
+            ]))
+            .create();
+
+        let client = OrcaClient::with_base_url(&mockito::server_url());
+        let pools = client
+            .best_pools_for_token("eclipse", ORCA_MINT, 2)
+            .await
+            .unwrap();
+        let addresses: Vec<&str> = pools.iter().map(|p| p.address.as_str()).collect();
+        assert_eq!(addresses, vec![deep]);
+
+        assert!(matches!(
+            client.best_pools_for_token("eclipse", ORCA_MINT, 0).await,
+            Err(OrcaError::Validation(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_all_tokens_dedupes_across_pages() {
+        let _first = mock("GET", "/solana/tokens")
+            .match_query(Matcher::Missing)
             .with_status(200)
             .with_header("content-type", "application/json")
             .with_body(
-                r#"{
-                    "data": [],
-                    "meta": {
-                        "next": null,
-                        "previous": null
-                    }
-                }"#,
+                serde_json::json!({
+                    "data": [fixtures::token_json(WSOL_MINT)],
+                    "meta": { "next": "page-2", "previous": null }
+                })
+                .to_string(),
             )
             .create();
+        let _second = mock("GET", "/solana/tokens?next=page-2")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![
+                fixtures::token_json(WSOL_MINT),
+                fixtures::token_json(USDC_MINT),
+            ]))
+            .create();
+
         let client = OrcaClient::with_base_url(&mockito::server_url());
-        let params = SearchPoolsParams {
-            q: "sol",
-            ..Default::default()
-        };
-        let result = client.search_pools("solana", params).await;
-        assert!(result.is_ok());
+        let tokens = client.get_all_tokens("solana").await.unwrap();
+        let addresses: Vec<&str> = tokens.iter().map(|t| t.address.as_str()).collect();
+        assert_eq!(addresses, vec![WSOL_MINT, USDC_MINT]);
     }
 
+    #[cfg(feature = "csv")]
     #[tokio::test]
-    async fn test_get_pool() {
-        let _m = mock(
-            "GET",
-            "/solana/pools/Czfq3xZZDmsdGdUyrNLtRhGc47cXcZtLG4crryfu44zE",
-        )
-        .with_status(200)
-        .with_header("content-type", "application/json")
-        .with_body(
-            r#"{
-                "data": [],
-                "meta": {
-                    "next": null,
-                    "previous": null
-                }
-            }"#,
-        )
-        .create();
+    async fn test_export_tokens_csv() {
+        let usdc = fixtures::merge(
+            fixtures::token_json(USDC_MINT),
+            serde_json::json!({
+                "decimals": 6,
+                "priceUsdc": "1.0",
+                "mintAuthority": "BJE5MMbqXjVwjAF7oxwPYXnTXDyspzZyt4vwenNw5ruG",
+                "metadata": r#"{"name":"USD Coin","symbol":"USDC"}"#
+            }),
+        );
+        let _m = mock("GET", "/solana/tokens")
+            .match_query(Matcher::Missing)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![usdc]))
+            .create();
+
         let client = OrcaClient::with_base_url(&mockito::server_url());
-        let result = client
-            .get_pool("solana", "Czfq3xZZDmsdGdUyrNLtRhGc47cXcZtLG4crryfu44zE")
-            .await;
-        assert!(result.is_ok());
+        let mut buffer = Vec::new();
+        client
+            .export_tokens_csv("solana", &mut buffer)
+            .await
+            .unwrap();
+        let csv = String::from_utf8(buffer).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("address,symbol,name,decimals,price_usdc,supply,has_mint_authority,has_freeze_authority")
+        );
+        assert_eq!(
+            lines.next(),
+            Some(format!("{USDC_MINT},USDC,USD Coin,6,1.0,1000000000,true,false").as_str())
+        );
+        assert_eq!(lines.next(), None);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,395 @@
+//! URL construction for each API endpoint, shared by the async and blocking clients.
+
+use crate::client::client::{GetPoolsParams, SearchPoolsParams, SearchTokensParams};
+use crate::error::error::OrcaError;
+use crate::models::models::Cursor;
+use reqwest::Url;
+use url::ParseError;
+
+/// Returns the path of `url` below the path of `base`, e.g. `/solana/pools` for
+/// `https://api.orca.so/v2/solana/pools` under `https://api.orca.so/v2`.
+///
+/// Both URLs are compared after parsing, so spellings that normalize to the same URL, such
+/// as an explicit default port, match. Returns `None` if `url` has another origin or its path
+/// is not under the base path.
+pub(crate) fn relative_path<'a>(base: &Url, url: &'a Url) -> Option<&'a str> {
+    if base.origin() != url.origin() {
+        return None;
+    }
+    let rest = url.path().strip_prefix(base.path().trim_end_matches('/'))?;
+    (rest.is_empty() || rest.starts_with('/')).then_some(rest)
+}
+
+/// Appends `segments` to the path of `base_url`, percent-encoding each one.
+///
+/// A segment containing `/`, `?` or `#` is encoded rather than splitting the path or starting
+/// a query, so untrusted addresses cannot change which endpoint is requested. Empty, `.`
+/// and `..` segments are rejected because URL normalisation would drop or climb over them.
+fn endpoint_url(base_url: &str, segments: &[&str]) -> Result<Url, OrcaError> {
+    if let Some(segment) = segments
+        .iter()
+        .find(|segment| matches!(**segment, "" | "." | ".."))
+    {
+        return Err(OrcaError::Validation(format!(
+            "path segment {segment:?} is not allowed"
+        )));
+    }
+    let mut url = Url::parse(base_url)?;
+    url.path_segments_mut()
+        .map_err(|_| ParseError::RelativeUrlWithCannotBeABaseBase)?
+        .pop_if_empty()
+        .extend(segments);
+    Ok(url)
+}
+
+pub(crate) fn protocol(base_url: &str, chain: &str) -> Result<Url, OrcaError> {
+    endpoint_url(base_url, &[chain, "protocol"])
+}
+
+pub(crate) fn token_info(base_url: &str, chain: &str) -> Result<Url, OrcaError> {
+    endpoint_url(base_url, &[chain, "protocol", "token"])
+}
+
+pub(crate) fn circulating_supply(base_url: &str, chain: &str) -> Result<Url, OrcaError> {
+    endpoint_url(
+        base_url,
+        &[chain, "protocol", "token", "circulating_supply"],
+    )
+}
+
+pub(crate) fn total_supply(base_url: &str, chain: &str) -> Result<Url, OrcaError> {
+    endpoint_url(base_url, &[chain, "protocol", "token", "total_supply"])
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn tokens(
+    base_url: &str,
+    chain: &str,
+    next: Option<&Cursor>,
+    previous: Option<&Cursor>,
+    size: Option<u32>,
+    sort_by: Option<&str>,
+    sort_direction: Option<&str>,
+    tokens: Option<&str>,
+) -> Result<Url, OrcaError> {
+    let mut url = endpoint_url(base_url, &[chain, "tokens"])?;
+
+    if let Some(next) = next {
+        url.query_pairs_mut().append_pair("next", next.as_str());
+    }
+    if let Some(previous) = previous {
+        url.query_pairs_mut()
+            .append_pair("previous", previous.as_str());
+    }
+    if let Some(size) = size {
+        url.query_pairs_mut().append_pair("size", &size.to_string());
+    }
+    if let Some(sort_by) = sort_by {
+        url.query_pairs_mut().append_pair("sort_by", sort_by);
+    }
+    if let Some(sort_direction) = sort_direction {
+        url.query_pairs_mut()
+            .append_pair("sort_direction", sort_direction);
+    }
+    if let Some(tokens) = tokens {
+        url.query_pairs_mut().append_pair("tokens", tokens);
+    }
+
+    Ok(url)
+}
+
+pub(crate) fn search_tokens(
+    base_url: &str,
+    chain: &str,
+    params: &SearchTokensParams<'_>,
+) -> Result<Url, OrcaError> {
+    let mut url = endpoint_url(base_url, &[chain, "tokens", "search"])?;
+    let mut query_pairs = url.query_pairs_mut();
+
+    query_pairs.append_pair("q", params.q);
+
+    if let Some(next) = params.next {
+        query_pairs.append_pair("next", next.as_str());
+    }
+    if let Some(size) = params.size {
+        query_pairs.append_pair("size", &size.to_string());
+    }
+    if let Some(sort_by) = params.sort_by {
+        query_pairs.append_pair("sortBy", sort_by);
+    }
+    if let Some(sort_direction) = params.sort_direction {
+        query_pairs.append_pair("sortDirection", sort_direction);
+    }
+
+    drop(query_pairs);
+    Ok(url)
+}
+
+pub(crate) fn token(base_url: &str, chain: &str, mint_address: &str) -> Result<Url, OrcaError> {
+    endpoint_url(base_url, &[chain, "tokens", mint_address])
+}
+
+pub(crate) fn lock_info(base_url: &str, chain: &str, address: &str) -> Result<Url, OrcaError> {
+    endpoint_url(base_url, &[chain, "lock", address])
+}
+
+/// Builds the `/pools` URL for `params`, after validating them.
+pub(crate) fn pools(
+    base_url: &str,
+    chain: &str,
+    params: &GetPoolsParams<'_>,
+) -> Result<Url, OrcaError> {
+    params.validate()?;
+    let mut url = endpoint_url(base_url, &[chain, "pools"])?;
+    let mut query_pairs = url.query_pairs_mut();
+
+    if let Some(sort_by) = params.sort_by {
+        query_pairs.append_pair("sortBy", sort_by);
+    }
+    if let Some(sort_direction) = params.sort_direction {
+        query_pairs.append_pair("sortDirection", sort_direction);
+    }
+    if let Some(next) = params.next {
+        query_pairs.append_pair("next", next.as_str());
+    }
+    if let Some(previous) = params.previous {
+        query_pairs.append_pair("previous", previous.as_str());
+    }
+    if let Some(has_rewards) = params.has_rewards {
+        query_pairs.append_pair("hasRewards", &has_rewards.to_string());
+    }
+    if let Some(has_warning) = params.has_warning {
+        query_pairs.append_pair("hasWarning", &has_warning.to_string());
+    }
+    if let Some(has_adaptive_fee) = params.has_adaptive_fee {
+        query_pairs.append_pair("hasAdaptiveFee", &has_adaptive_fee.to_string());
+    }
+    if let Some(is_wavebreak) = params.is_wavebreak {
+        query_pairs.append_pair("isWavebreak", &is_wavebreak.to_string());
+    }
+    if let Some(min_tvl) = params.min_tvl {
+        query_pairs.append_pair("minTvl", &min_tvl.to_string());
+    }
+    if let Some(min_volume) = params.min_volume {
+        query_pairs.append_pair("minVolume", &min_volume.to_string());
+    }
+    if let Some(min_locked_liquidity_percent) = params.min_locked_liquidity_percent {
+        query_pairs.append_pair(
+            "minLockedLiquidityPercent",
+            &min_locked_liquidity_percent.to_string(),
+        );
+    }
+    if let Some(size) = params.size {
+        query_pairs.append_pair("size", &size.to_string());
+    }
+    if let Some(token) = params.token {
+        for t in token {
+            query_pairs.append_pair("token", t);
+        }
+    }
+    if let Some(tokens_both_of) = params.tokens_both_of {
+        for t in tokens_both_of {
+            query_pairs.append_pair("tokensBothOf", t);
+        }
+    }
+    if let Some(addresses) = params.addresses {
+        for a in addresses {
+            query_pairs.append_pair("addresses", a);
+        }
+    }
+    if let Some(stats) = params.stats {
+        for s in stats {
+            query_pairs.append_pair("stats", s.as_query_value());
+        }
+    }
+    if let Some(include_blocked) = params.include_blocked {
+        query_pairs.append_pair("includeBlocked", &include_blocked.to_string());
+    }
+
+    drop(query_pairs);
+    Ok(url)
+}
+
+pub(crate) fn search_pools(
+    base_url: &str,
+    chain: &str,
+    params: &SearchPoolsParams<'_>,
+) -> Result<Url, OrcaError> {
+    let mut url = endpoint_url(base_url, &[chain, "pools", "search"])?;
+    let mut query_pairs = url.query_pairs_mut();
+
+    query_pairs.append_pair("q", params.q);
+
+    if let Some(next) = params.next {
+        query_pairs.append_pair("next", next.as_str());
+    }
+    if let Some(size) = params.size {
+        query_pairs.append_pair("size", &size.to_string());
+    }
+    if let Some(sort_by) = params.sort_by {
+        query_pairs.append_pair("sortBy", sort_by);
+    }
+    if let Some(sort_direction) = params.sort_direction {
+        query_pairs.append_pair("sortDirection", sort_direction);
+    }
+    if let Some(min_tvl) = params.min_tvl {
+        query_pairs.append_pair("minTvl", &min_tvl.to_string());
+    }
+    if let Some(min_volume) = params.min_volume {
+        query_pairs.append_pair("minVolume", &min_volume.to_string());
+    }
+    if let Some(stats) = params.stats {
+        for s in stats {
+            query_pairs.append_pair("stats", s.as_query_value());
+        }
+    }
+    if let Some(user_tokens) = params.user_tokens {
+        for t in user_tokens {
+            query_pairs.append_pair("userTokens", t);
+        }
+    }
+    if let Some(has_rewards) = params.has_rewards {
+        query_pairs.append_pair("hasRewards", &has_rewards.to_string());
+    }
+    if let Some(verified_only) = params.verified_only {
+        query_pairs.append_pair("verifiedOnly", &verified_only.to_string());
+    }
+    if let Some(has_locked_liquidity) = params.has_locked_liquidity {
+        query_pairs.append_pair("hasLockedLiquidity", &has_locked_liquidity.to_string());
+    }
+
+    drop(query_pairs);
+    Ok(url)
+}
+
+pub(crate) fn pool(base_url: &str, chain: &str, address: &str) -> Result<Url, OrcaError> {
+    endpoint_url(base_url, &[chain, "pools", address])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::models::TimePeriod;
+
+    #[test]
+    fn test_stats_query_values() {
+        let table = [
+            (TimePeriod::M5, "5m"),
+            (TimePeriod::M15, "15m"),
+            (TimePeriod::M30, "30m"),
+            (TimePeriod::H1, "1h"),
+            (TimePeriod::H2, "2h"),
+            (TimePeriod::H4, "4h"),
+            (TimePeriod::H8, "8h"),
+            (TimePeriod::H12, "12h"),
+            (TimePeriod::H24, "24h"),
+        ];
+        let periods = table.map(|(period, _)| period);
+        let expected: Vec<(String, String)> = table
+            .iter()
+            .map(|(_, value)| ("stats".to_string(), value.to_string()))
+            .collect();
+
+        let pools_url = pools(
+            "https://api.orca.so/v2",
+            "solana",
+            &GetPoolsParams {
+                stats: Some(&periods),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let pairs: Vec<(String, String)> = pools_url.query_pairs().into_owned().collect();
+        assert_eq!(pairs, expected);
+
+        let search_url = search_pools(
+            "https://api.orca.so/v2",
+            "solana",
+            &SearchPoolsParams {
+                q: "SOL",
+                stats: Some(&[TimePeriod::H1, TimePeriod::H24]),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(search_url.query(), Some("q=SOL&stats=1h&stats=24h"));
+    }
+
+    #[test]
+    fn test_token_filter_sends_mint_addresses() {
+        let mints = [
+            "So11111111111111111111111111111111111111112",
+            "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
+        ];
+        let url = pools(
+            "https://api.orca.so/v2",
+            "solana",
+            &GetPoolsParams {
+                token: Some(&mints),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            url.query(),
+            Some(
+                "token=So11111111111111111111111111111111111111112\
+                 &token=EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"
+            )
+        );
+    }
+
+    #[test]
+    fn test_path_segments_are_encoded() {
+        let url = pool("https://api.orca.so/v2", "solana", "abc/def?x=1#y").unwrap();
+        assert_eq!(url.path(), "/v2/solana/pools/abc%2Fdef%3Fx=1%23y");
+        assert_eq!(url.query(), None);
+        assert_eq!(url.fragment(), None);
+
+        let url = lock_info("http://localhost:1234", "solana", "../tokens").unwrap();
+        assert_eq!(
+            url.as_str(),
+            "http://localhost:1234/solana/lock/..%2Ftokens"
+        );
+
+        assert!(protocol("mailto:dev@example.com", "solana").is_err());
+    }
+
+    #[test]
+    fn test_relative_path() {
+        let base = Url::parse("http://Orca.example:80/v2/").unwrap();
+        let url = total_supply("http://orca.example/v2", "solana").unwrap();
+        assert_eq!(
+            relative_path(&base, &url),
+            Some("/solana/protocol/token/total_supply")
+        );
+
+        let root = Url::parse("http://orca.example").unwrap();
+        assert_eq!(relative_path(&root, &url), Some(url.path()));
+
+        for other in [
+            "https://orca.example/v2",
+            "http://orca.example:8080/v2",
+            "http://orca.example/v",
+            "http://orca.example/v3",
+        ] {
+            let other = Url::parse(other).unwrap();
+            assert_eq!(relative_path(&other, &url), None, "{other}");
+        }
+    }
+
+    #[test]
+    fn test_dot_and_empty_segments_are_rejected() {
+        for address in ["", ".", ".."] {
+            let err = pool("https://api.orca.so/v2", "solana", address).unwrap_err();
+            assert!(
+                matches!(err, OrcaError::Validation(_)),
+                "{address:?}: {err:?}"
+            );
+        }
+        assert!(matches!(
+            protocol("https://api.orca.so/v2", ".."),
+            Err(OrcaError::Validation(_))
+        ));
+    }
+}
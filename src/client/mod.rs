@@ -1 +1,13 @@
-pub mod client;
\ No newline at end of file
+pub mod api;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+mod cache;
+#[allow(clippy::module_inception)]
+pub mod client;
+mod endpoints;
+pub mod observer;
+mod rate_limit;
+#[cfg(feature = "record-replay")]
+pub mod replay;
+mod rt;
+pub mod transport;
@@ -0,0 +1,93 @@
+//! Hooks for observing the requests an `OrcaClient` sends, e.g. to export metrics.
+//!
+//! An `OrcaObserver` set with `OrcaClientBuilder::with_observer` is called around every HTTP
+//! request, retries included, so the client can feed any metrics system without this crate
+//! depending on it. Responses served from the cache send no request and are not observed.
+
+use crate::client::transport::{MaybeSend, MaybeSync};
+use crate::error::error::OrcaError;
+use reqwest::Url;
+use std::time::Duration;
+
+/// Receives a callback for each request an `OrcaClient` sends.
+///
+/// Every method defaults to doing nothing. Callbacks run inline on the request path, so they
+/// should return quickly.
+pub trait OrcaObserver: MaybeSend + MaybeSync {
+    /// Called just before a request to `url` is sent.
+    fn on_request(&self, url: &Url) {
+        let _ = url;
+    }
+
+    /// Called when a request to `url` succeeds with `status`, `elapsed` after it was sent.
+    fn on_response(&self, url: &Url, status: u16, elapsed: Duration) {
+        let _ = (url, status, elapsed);
+    }
+
+    /// Called when a request to `url` fails, including with a non-success status.
+    fn on_error(&self, url: &Url, error: &OrcaError) {
+        let _ = (url, error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::client::OrcaClient;
+    use mockito::mock;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    /// Counts callbacks and remembers the statuses it saw.
+    #[derive(Default)]
+    struct CountingObserver {
+        requests: AtomicU32,
+        responses: AtomicU32,
+        errors: AtomicU32,
+        statuses: Mutex<Vec<u16>>,
+    }
+
+    impl OrcaObserver for CountingObserver {
+        fn on_request(&self, _: &Url) {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn on_response(&self, _: &Url, status: u16, _: Duration) {
+            self.responses.fetch_add(1, Ordering::SeqCst);
+            self.statuses.lock().unwrap().push(status);
+        }
+
+        fn on_error(&self, _: &Url, error: &OrcaError) {
+            self.errors.fetch_add(1, Ordering::SeqCst);
+            if let OrcaError::Api { status, .. } = error {
+                self.statuses.lock().unwrap().push(*status);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_counting_observer() {
+        let _ok = mock("GET", "/devnet/protocol/token/total_supply")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"total_supply": "99999713"}"#)
+            .create();
+        let _fail = mock("GET", "/devnet/protocol/token/circulating_supply")
+            .with_status(503)
+            .create();
+
+        let observer = Arc::new(CountingObserver::default());
+        let client = OrcaClient::builder()
+            .with_base_url(&mockito::server_url())
+            .with_observer(observer.clone())
+            .build()
+            .unwrap();
+        client.get_total_supply("devnet").await.unwrap();
+        assert!(client.get_circulating_supply("devnet").await.is_err());
+
+        assert_eq!(observer.requests.load(Ordering::SeqCst), 2);
+        assert_eq!(observer.responses.load(Ordering::SeqCst), 1);
+        assert_eq!(observer.errors.load(Ordering::SeqCst), 1);
+        assert_eq!(*observer.statuses.lock().unwrap(), vec![200, 503]);
+    }
+}
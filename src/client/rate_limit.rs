@@ -0,0 +1,32 @@
+use crate::client::rt::{self, Instant};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Spaces outgoing requests so that at most `requests_per_second` start in any second.
+pub(crate) struct RateLimiter {
+    interval: Duration,
+    next_slot: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    /// Creates a limiter; `requests_per_second` must be non-zero, which the builder checks.
+    pub(crate) fn new(requests_per_second: u32) -> Self {
+        Self {
+            interval: Duration::from_secs(1) / requests_per_second,
+            next_slot: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Waits until the caller may send its request.
+    ///
+    /// Slots are handed out in call order, so concurrent callers queue rather than burst.
+    pub(crate) async fn acquire(&self) {
+        let slot = {
+            let mut next_slot = self.next_slot.lock().unwrap_or_else(|e| e.into_inner());
+            let slot = (*next_slot).max(Instant::now());
+            *next_slot = slot + self.interval;
+            slot
+        };
+        rt::sleep_until(slot).await;
+    }
+}
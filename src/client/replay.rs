@@ -0,0 +1,292 @@
+//! Recording and replaying API responses, enabled by the `record-replay` feature.
+//!
+//! An `OrcaClient` built with `OrcaClientBuilder::with_recording` writes every successful
+//! response it receives to a directory, one JSON file per request. A `ReplayClient` pointed at
+//! that directory then answers the same requests from the files, through the `OrcaApi` trait,
+//! without any network access.
+//!
+//! Recordings are keyed by the request path and query relative to the base URL, e.g.
+//! `/solana/pools?size=5`, so responses recorded against one host replay for any other. Only
+//! successful responses are recorded; a request that was never recorded fails with
+//! `OrcaError::NotFound` when replayed.
+
+use crate::client::api::OrcaApi;
+use crate::client::client::{GetPoolsParams, SearchPoolsParams, SearchTokensParams};
+use crate::client::endpoints;
+use crate::error::error::OrcaError;
+use crate::models::models::{
+    CirculatingSupplyResponse, Cursor, LockInfo, Paginated, ProtocolInfo, Token, TokenInfo,
+    TotalSupplyResponse, Whirlpool,
+};
+use reqwest::Url;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// The base URL endpoint paths are resolved against when replaying.
+const REPLAY_BASE_URL: &str = "http://replay.invalid";
+
+/// A recorded request and its response body.
+#[derive(Serialize, Deserialize)]
+struct Recording {
+    request: String,
+    body: serde_json::Value,
+}
+
+/// Returns the path and query of `url` below `base`, which identifies a recording.
+///
+/// The comparison is made on the parsed URLs, so a base URL written in a non-canonical form
+/// still yields keys such as `/solana/pools?size=5`. A URL outside `base` is keyed by the
+/// whole URL.
+pub(crate) fn request_key(base: &Url, url: &Url) -> String {
+    let Some(path) = endpoints::relative_path(base, url) else {
+        return url.to_string();
+    };
+    match url.query() {
+        Some(query) => format!("{path}?{query}"),
+        None => path.to_string(),
+    }
+}
+
+/// Returns the file a recording of `request` is stored in.
+///
+/// The name is a 64-bit FNV-1a hash of the request, which is stable across builds and
+/// platforms.
+fn recording_path(dir: &Path, request: &str) -> PathBuf {
+    let hash = request
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+    dir.join(format!("{:016x}.json", hash))
+}
+
+/// Writes `body`, the response to `request`, to `dir`.
+///
+/// Bodies that are not JSON are stored as a JSON string.
+pub(crate) async fn record(dir: &Path, request: String, body: &[u8]) -> Result<(), OrcaError> {
+    let body = serde_json::from_slice(body)
+        .unwrap_or_else(|_| serde_json::Value::String(String::from_utf8_lossy(body).into_owned()));
+    let path = recording_path(dir, &request);
+    let recording = serde_json::to_vec_pretty(&Recording { request, body })?;
+    tokio::fs::create_dir_all(dir).await?;
+    tokio::fs::write(path, recording).await?;
+    Ok(())
+}
+
+/// Serves responses recorded by an `OrcaClient` built with `with_recording`.
+#[derive(Debug, Clone)]
+pub struct ReplayClient {
+    dir: PathBuf,
+    default_pool_params: GetPoolsParams<'static>,
+}
+
+impl ReplayClient {
+    /// Creates a client replaying the recordings in `dir`.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            default_pool_params: GetPoolsParams::default(),
+        }
+    }
+
+    /// Merges `params` into every `/pools` request, like
+    /// `OrcaClientBuilder::with_default_pool_params`.
+    ///
+    /// Set the same defaults the recording client used, or `get_pools` looks up a different
+    /// request than the one that was recorded.
+    pub fn with_default_pool_params(mut self, params: GetPoolsParams<'static>) -> Self {
+        self.default_pool_params = params;
+        self
+    }
+
+    /// Loads the recorded response for `url` and deserializes it.
+    async fn replay<T: DeserializeOwned>(&self, url: Url) -> Result<T, OrcaError> {
+        let request = request_key(&Url::parse(REPLAY_BASE_URL)?, &url);
+        let contents = match tokio::fs::read(recording_path(&self.dir, &request)).await {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                return Err(OrcaError::NotFound {
+                    resource: "recording",
+                    id: request,
+                })
+            }
+            Err(err) => return Err(err.into()),
+        };
+        let recording: Recording = serde_json::from_slice(&contents)?;
+        Ok(serde_json::from_value(recording.body)?)
+    }
+}
+
+impl OrcaApi for ReplayClient {
+    async fn get_protocol_info(&self, chain: &str) -> Result<ProtocolInfo, OrcaError> {
+        self.replay(endpoints::protocol(REPLAY_BASE_URL, chain)?)
+            .await
+    }
+
+    async fn get_token_info(&self, chain: &str) -> Result<TokenInfo, OrcaError> {
+        self.replay(endpoints::token_info(REPLAY_BASE_URL, chain)?)
+            .await
+    }
+
+    async fn get_circulating_supply(
+        &self,
+        chain: &str,
+    ) -> Result<CirculatingSupplyResponse, OrcaError> {
+        self.replay(endpoints::circulating_supply(REPLAY_BASE_URL, chain)?)
+            .await
+    }
+
+    async fn get_total_supply(&self, chain: &str) -> Result<TotalSupplyResponse, OrcaError> {
+        self.replay(endpoints::total_supply(REPLAY_BASE_URL, chain)?)
+            .await
+    }
+
+    async fn get_tokens(
+        &self,
+        chain: &str,
+        next: Option<&Cursor>,
+        previous: Option<&Cursor>,
+        size: Option<u32>,
+        sort_by: Option<&str>,
+        sort_direction: Option<&str>,
+        tokens: Option<&str>,
+    ) -> Result<Paginated<Token>, OrcaError> {
+        self.replay(endpoints::tokens(
+            REPLAY_BASE_URL,
+            chain,
+            next,
+            previous,
+            size,
+            sort_by,
+            sort_direction,
+            tokens,
+        )?)
+        .await
+    }
+
+    async fn search_tokens_with_params(
+        &self,
+        chain: &str,
+        params: SearchTokensParams<'_>,
+    ) -> Result<Paginated<Token>, OrcaError> {
+        self.replay(endpoints::search_tokens(REPLAY_BASE_URL, chain, &params)?)
+            .await
+    }
+
+    async fn get_token(
+        &self,
+        chain: &str,
+        mint_address: &str,
+    ) -> Result<Paginated<Token>, OrcaError> {
+        self.replay(endpoints::token(REPLAY_BASE_URL, chain, mint_address)?)
+            .await
+    }
+
+    async fn get_lock_info(&self, chain: &str, address: &str) -> Result<Vec<LockInfo>, OrcaError> {
+        self.replay(endpoints::lock_info(REPLAY_BASE_URL, chain, address)?)
+            .await
+    }
+
+    async fn get_pools(
+        &self,
+        chain: &str,
+        params: GetPoolsParams<'_>,
+    ) -> Result<Paginated<Whirlpool>, OrcaError> {
+        let params = params.or_defaults(self.default_pool_params);
+        self.replay(endpoints::pools(REPLAY_BASE_URL, chain, &params)?)
+            .await
+    }
+
+    async fn search_pools(
+        &self,
+        chain: &str,
+        params: SearchPoolsParams<'_>,
+    ) -> Result<Paginated<Whirlpool>, OrcaError> {
+        self.replay(endpoints::search_pools(REPLAY_BASE_URL, chain, &params)?)
+            .await
+    }
+
+    async fn get_pool(
+        &self,
+        chain: &str,
+        address: &str,
+    ) -> Result<Paginated<Whirlpool>, OrcaError> {
+        self.replay(endpoints::pool(REPLAY_BASE_URL, chain, address)?)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::client::OrcaClient;
+    use crate::models::fixtures::{self, SOL_USDC_POOL};
+    use mockito::mock;
+
+    #[tokio::test]
+    async fn test_record_then_replay() {
+        let dir = std::env::temp_dir().join(format!("orca-replay-{}", std::process::id()));
+        let m = mock("GET", "/eclipse/pools?size=2")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(fixtures::paginated(vec![fixtures::whirlpool_json()]))
+            .expect(1)
+            .create();
+
+        let defaults = GetPoolsParams {
+            size: Some(2),
+            ..Default::default()
+        };
+        let params = GetPoolsParams::default();
+        // A base URL that `Url` normalizes must still produce replayable keys.
+        let server = mockito::server_url().replacen("http", "HTTP", 1);
+        let recorder = OrcaClient::builder()
+            .with_base_url(&format!("{server}/./"))
+            .with_default_pool_params(defaults)
+            .with_recording(&dir)
+            .build()
+            .unwrap();
+        let live = recorder.get_pools("eclipse", params).await.unwrap();
+        m.assert();
+
+        let replay = ReplayClient::new(&dir).with_default_pool_params(defaults);
+        let replayed = OrcaApi::get_pools(&replay, "eclipse", params)
+            .await
+            .unwrap();
+        assert_eq!(replayed.data.len(), live.data.len());
+        assert_eq!(replayed.data[0].address, SOL_USDC_POOL);
+
+        match replay.get_pool("eclipse", SOL_USDC_POOL).await {
+            Err(OrcaError::NotFound { resource, id }) => {
+                assert_eq!(resource, "recording");
+                assert_eq!(id, format!("/eclipse/pools/{}", SOL_USDC_POOL));
+            }
+            other => panic!("expected NotFound, got {:?}", other),
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_request_key() {
+        let url = endpoints::pools(
+            "https://api.orca.so/v2",
+            "solana",
+            &GetPoolsParams {
+                size: Some(5),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        for base in ["https://api.orca.so/v2", "HTTPS://api.orca.so:443/v2/"] {
+            assert_eq!(
+                request_key(&Url::parse(base).unwrap(), &url),
+                "/solana/pools?size=5"
+            );
+        }
+        assert_ne!(
+            recording_path(Path::new("."), "/solana/pools?size=5"),
+            recording_path(Path::new("."), "/solana/pools?size=6")
+        );
+    }
+}
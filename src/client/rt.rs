@@ -0,0 +1,42 @@
+//! Timers and clocks that run on tokio and std natively and on browser APIs under wasm32.
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use std::time::SystemTime;
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use tokio::time::{sleep, sleep_until, Instant};
+#[cfg(target_arch = "wasm32")]
+pub(crate) use web_time::{Instant, SystemTime};
+
+use std::time::Duration;
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) async fn sleep(duration: Duration) {
+    gloo_timers::future::sleep(duration).await;
+}
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) async fn sleep_until(deadline: Instant) {
+    sleep(deadline.saturating_duration_since(Instant::now())).await;
+}
+
+/// Yields immediately on the first `tick`, then once every `period`.
+///
+/// A tick that fires late delays the following ones rather than bursting to catch up.
+pub(crate) struct Interval {
+    period: Duration,
+    next: Instant,
+}
+
+impl Interval {
+    pub(crate) fn new(period: Duration) -> Self {
+        Self {
+            period,
+            next: Instant::now(),
+        }
+    }
+
+    pub(crate) async fn tick(&mut self) {
+        sleep_until(self.next).await;
+        self.next = Instant::now() + self.period;
+    }
+}
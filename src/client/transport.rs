@@ -0,0 +1,325 @@
+//! The HTTP layer under `OrcaClient`, abstracted so it can run on other HTTP stacks.
+//!
+//! `OrcaClient` is generic over an `HttpTransport` and defaults to `ReqwestTransport`. URL
+//! construction, retries, caching, rate limiting and deserialization all happen above the
+//! transport, so an implementation only has to send a GET request and hand back the body.
+
+use crate::client::rt::SystemTime;
+use crate::error::error::OrcaError;
+use bytes::Bytes;
+use chrono::{DateTime, NaiveDateTime};
+use reqwest::header::{CONTENT_TYPE, RETRY_AFTER};
+use reqwest::{Client, Response, Url};
+use std::future::Future;
+use std::time::Duration;
+
+/// `Send` on native targets. Under wasm32, where the browser's futures are not `Send`, it is
+/// implemented for every type.
+#[cfg(not(target_arch = "wasm32"))]
+pub trait MaybeSend: Send {}
+#[cfg(not(target_arch = "wasm32"))]
+impl<T: Send + ?Sized> MaybeSend for T {}
+#[cfg(target_arch = "wasm32")]
+pub trait MaybeSend {}
+#[cfg(target_arch = "wasm32")]
+impl<T: ?Sized> MaybeSend for T {}
+
+/// `Sync` on native targets and implemented for every type under wasm32, like `MaybeSend`.
+#[cfg(not(target_arch = "wasm32"))]
+pub trait MaybeSync: Sync {}
+#[cfg(not(target_arch = "wasm32"))]
+impl<T: Sync + ?Sized> MaybeSync for T {}
+#[cfg(target_arch = "wasm32")]
+pub trait MaybeSync {}
+#[cfg(target_arch = "wasm32")]
+impl<T: ?Sized> MaybeSync for T {}
+
+/// The status and rate-limit headers of a successful response, used to build `ResponseMeta`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResponseHeaders {
+    /// The HTTP status code.
+    pub status: u16,
+    /// The `x-ratelimit-limit` header.
+    pub rate_limit_limit: Option<u64>,
+    /// The `x-ratelimit-remaining` header.
+    pub rate_limit_remaining: Option<u64>,
+    /// The `x-ratelimit-reset` header.
+    pub rate_limit_reset: Option<u64>,
+}
+
+/// Sends GET requests for `OrcaClient`.
+///
+/// Implementations return the body of successful responses, and `OrcaError::Api` with the
+/// status, body and `Retry-After` delay of any other response so that the client can decide
+/// whether and when to retry. `parse_retry_after` decodes the header.
+pub trait HttpTransport: Clone + MaybeSend + MaybeSync + 'static {
+    /// Sends a GET request to `url` and returns the response body.
+    fn get(&self, url: Url) -> impl Future<Output = Result<Bytes, OrcaError>> + MaybeSend;
+
+    /// Like `get`, also returning the status and rate-limit headers of the response.
+    ///
+    /// The default reports a `200` status without rate-limit headers; override it for
+    /// transports that can read response headers.
+    fn get_with_headers(
+        &self,
+        url: Url,
+    ) -> impl Future<Output = Result<(Bytes, ResponseHeaders), OrcaError>> + MaybeSend {
+        async move {
+            let body = self.get(url).await?;
+            let headers = ResponseHeaders {
+                status: 200,
+                ..Default::default()
+            };
+            Ok((body, headers))
+        }
+    }
+}
+
+/// Parses a `Retry-After` header value into the delay it asks for, counted from `now`.
+///
+/// The value is either a number of seconds or an HTTP-date, in the IMF-fixdate form
+/// (`Wed, 21 Oct 2015 07:28:00 GMT`) or one of the obsolete RFC 850 and asctime forms. A
+/// date in the past is a zero delay. Returns `None` for anything else.
+pub fn parse_retry_after(value: &str, now: SystemTime) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = DateTime::parse_from_rfc2822(value)
+        .map(|date| date.naive_utc())
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%A, %d-%b-%y %H:%M:%S GMT"))
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%a %b %e %H:%M:%S %Y"))
+        .ok()?;
+    let timestamp = u64::try_from(date.and_utc().timestamp()).ok()?;
+    let at = SystemTime::UNIX_EPOCH.checked_add(Duration::from_secs(timestamp))?;
+    Some(at.duration_since(now).unwrap_or(Duration::ZERO))
+}
+
+/// How many characters of a non-JSON body `OrcaError::UnexpectedContentType` keeps.
+const BODY_SNIPPET_CHARS: usize = 200;
+
+/// Checks that a successful response declared a JSON body.
+///
+/// Any media type ending in `json`, such as `application/json` or `application/problem+json`,
+/// passes, as does a response without a `Content-Type` header. Anything else fails with
+/// `OrcaError::UnexpectedContentType` carrying the start of `body`.
+pub(crate) fn check_json_content_type(
+    content_type: Option<&str>,
+    body: &[u8],
+) -> Result<(), OrcaError> {
+    let Some(content_type) = content_type else {
+        return Ok(());
+    };
+    let essence = content_type.split(';').next().unwrap_or_default().trim();
+    if essence.to_ascii_lowercase().ends_with("json") {
+        return Ok(());
+    }
+    let body = String::from_utf8_lossy(body);
+    Err(OrcaError::UnexpectedContentType {
+        content_type: content_type.to_string(),
+        body_snippet: body.trim().chars().take(BODY_SNIPPET_CHARS).collect(),
+    })
+}
+
+/// The default transport, backed by `reqwest`.
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    client: Client,
+}
+
+impl ReqwestTransport {
+    /// Wraps a configured `reqwest::Client`.
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Sends the request, turning non-success responses into `OrcaError::Api`.
+    async fn send(&self, url: Url) -> Result<Response, OrcaError> {
+        let response = self.client.get(url).send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let retry_after = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| parse_retry_after(value, SystemTime::now()));
+            let body = response.text().await.unwrap_or_default();
+            return Err(OrcaError::Api {
+                status: status.as_u16(),
+                body,
+                retry_after,
+            });
+        }
+        Ok(response)
+    }
+
+    /// Reads the body of a successful response, failing if it is not JSON.
+    async fn json_body(response: Response) -> Result<Bytes, OrcaError> {
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let body = response.bytes().await?;
+        check_json_content_type(content_type.as_deref(), &body)?;
+        Ok(body)
+    }
+}
+
+impl HttpTransport for ReqwestTransport {
+    async fn get(&self, url: Url) -> Result<Bytes, OrcaError> {
+        Self::json_body(self.send(url).await?).await
+    }
+
+    async fn get_with_headers(&self, url: Url) -> Result<(Bytes, ResponseHeaders), OrcaError> {
+        let response = self.send(url).await?;
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse().ok())
+        };
+        let headers = ResponseHeaders {
+            status: response.status().as_u16(),
+            rate_limit_limit: header("x-ratelimit-limit"),
+            rate_limit_remaining: header("x-ratelimit-remaining"),
+            rate_limit_reset: header("x-ratelimit-reset"),
+        };
+        Ok((Self::json_body(response).await?, headers))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::client::{GetPoolsParams, OrcaClient};
+    use crate::models::fixtures::{self, SOL_USDC_POOL};
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    /// Serves a page with one pool after failing the first `failures` requests with a `503`.
+    #[derive(Clone, Default)]
+    struct FakeTransport {
+        failures: u32,
+        calls: Arc<AtomicU32>,
+        urls: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl HttpTransport for FakeTransport {
+        async fn get(&self, url: Url) -> Result<Bytes, OrcaError> {
+            self.urls.lock().unwrap().push(url.to_string());
+            if self.calls.fetch_add(1, Ordering::SeqCst) < self.failures {
+                return Err(OrcaError::Api {
+                    status: 503,
+                    body: "unavailable".to_string(),
+                    retry_after: None,
+                });
+            }
+            Ok(fixtures::paginated(vec![fixtures::whirlpool_json()]).into())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_custom_transport() {
+        let transport = FakeTransport {
+            failures: 1,
+            ..Default::default()
+        };
+        let client = OrcaClient::builder()
+            .with_base_url("http://orca.invalid/v2")
+            .with_retries(1, Duration::from_millis(1))
+            .build_with_transport(transport.clone())
+            .unwrap();
+
+        let pool = client
+            .get_pool_single("solana", SOL_USDC_POOL)
+            .await
+            .unwrap();
+        assert_eq!(pool.address, SOL_USDC_POOL);
+        assert_eq!(transport.calls.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            transport.urls.lock().unwrap()[0],
+            format!("http://orca.invalid/v2/solana/pools/{}", SOL_USDC_POOL)
+        );
+
+        let (page, meta) = client
+            .get_pools_with_meta("solana", GetPoolsParams::default())
+            .await
+            .unwrap();
+        assert_eq!(page.data.len(), 1);
+        assert_eq!(meta.status, 200);
+        assert_eq!(meta.rate_limit_limit, None);
+        assert!(client.bytes_received() > 0);
+    }
+
+    /// `Wed, 21 Oct 2015 07:28:00 GMT`.
+    fn at_2015_10_21() -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(1_445_412_480)
+    }
+
+    #[test]
+    fn test_parse_retry_after_seconds() {
+        let now = at_2015_10_21();
+        assert_eq!(
+            parse_retry_after("120", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(parse_retry_after(" 0 ", now), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_parse_retry_after_date() {
+        let now = at_2015_10_21();
+        for value in [
+            "Wed, 21 Oct 2015 07:29:30 GMT",
+            "Wednesday, 21-Oct-15 07:29:30 GMT",
+            "Wed Oct 21 07:29:30 2015",
+        ] {
+            assert_eq!(
+                parse_retry_after(value, now),
+                Some(Duration::from_secs(90)),
+                "{}",
+                value
+            );
+        }
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:00:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+    }
+
+    #[test]
+    fn test_check_json_content_type() {
+        for content_type in [
+            None,
+            Some("application/json"),
+            Some("application/json; charset=utf-8"),
+            Some("Application/Problem+JSON"),
+        ] {
+            assert!(check_json_content_type(content_type, b"{}").is_ok());
+        }
+
+        let page = format!("<html>{}</html>", "x".repeat(500));
+        match check_json_content_type(Some("text/html"), page.as_bytes()) {
+            Err(OrcaError::UnexpectedContentType {
+                content_type,
+                body_snippet,
+            }) => {
+                assert_eq!(content_type, "text/html");
+                assert_eq!(body_snippet.len(), BODY_SNIPPET_CHARS);
+                assert!(body_snippet.starts_with("<html>"));
+            }
+            other => panic!("expected UnexpectedContentType, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_retry_after_garbage() {
+        let now = at_2015_10_21();
+        for value in ["", "soon", "-5", "1.5", "Wed, 32 Oct 2015 07:28:00 GMT"] {
+            assert_eq!(parse_retry_after(value, now), None, "{}", value);
+        }
+    }
+}
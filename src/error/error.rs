@@ -0,0 +1,92 @@
+use std::time::Duration;
+use thiserror::Error;
+
+/// Errors returned by the Orca client.
+#[derive(Debug, Error)]
+pub enum OrcaError {
+    /// The HTTP request failed or the response body could not be decoded.
+    #[error("http error: {0}")]
+    Http(#[from] reqwest::Error),
+    /// The response body was not the expected JSON.
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+    /// Writing CSV output failed.
+    #[cfg(feature = "csv")]
+    #[error("csv error: {0}")]
+    Csv(#[from] csv::Error),
+    /// Reading or writing a recording failed.
+    #[cfg(feature = "record-replay")]
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    /// The request URL could not be built.
+    #[error("invalid url: {0}")]
+    InvalidUrl(#[from] url::ParseError),
+    /// The API responded with a non-success status code.
+    ///
+    /// `retry_after` holds the delay requested by the response's `Retry-After` header, if it
+    /// had a valid one.
+    #[error("api returned status {status}: {body}")]
+    Api {
+        status: u16,
+        body: String,
+        retry_after: Option<Duration>,
+    },
+    /// A successful response was not JSON, e.g. an HTML error page served by a CDN.
+    ///
+    /// `body_snippet` holds the start of the body.
+    #[error("expected a JSON response, got {content_type}: {body_snippet}")]
+    UnexpectedContentType {
+        content_type: String,
+        body_snippet: String,
+    },
+    /// A numeric field could not be parsed.
+    #[error("invalid number in `{field}`: {value:?}")]
+    InvalidNumber { field: &'static str, value: String },
+    /// A computation overflowed the range of its numeric type.
+    #[error("arithmetic overflow computing {0}")]
+    Overflow(&'static str),
+    /// A field expected to hold a base58 public key does not.
+    #[error("invalid public key in `{field}`: {value:?}")]
+    InvalidPubkey { field: &'static str, value: String },
+    /// The pool's seeds do not derive a valid program address.
+    #[error("the pool seeds do not derive a valid program address")]
+    InvalidSeeds,
+    /// The address derived from a pool's seeds differs from the address it reports.
+    #[error("derived address {derived} does not match reported address {reported}")]
+    AddressMismatch { derived: String, reported: String },
+    /// A value failed a consistency check.
+    #[error("validation failed: {0}")]
+    Validation(String),
+    /// A single-resource endpoint returned an empty `data` array.
+    #[error("the response contained no data")]
+    EmptyResult,
+    /// The client has received more response bytes than its configured quota.
+    #[error("byte quota of {limit} exceeded: {received} bytes received")]
+    QuotaExceeded { limit: u64, received: u64 },
+    /// The requested resource does not exist.
+    #[error("{resource} {id} not found")]
+    NotFound { resource: &'static str, id: String },
+}
+
+impl OrcaError {
+    /// Returns `true` if the request that produced this error may succeed when retried.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            #[cfg(not(target_arch = "wasm32"))]
+            OrcaError::Http(err) => err.is_connect() || err.is_timeout(),
+            #[cfg(target_arch = "wasm32")]
+            OrcaError::Http(err) => err.is_request() || err.is_timeout(),
+            OrcaError::Api { status, .. } => *status == 429 || *status >= 500,
+            _ => false,
+        }
+    }
+
+    /// Returns the delay the server asked for before retrying, from the `Retry-After` header
+    /// of an `OrcaError::Api` response.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            OrcaError::Api { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+}
@@ -5,7 +5,7 @@
 //! ## Usage
 //!
 //! ```rust,no_run
-//! use orca_public_api_client::client::client::OrcaClient;
+//! use api_orca_so_rs::client::client::OrcaClient;
 //!
 //! #[tokio::main]
 //! async fn main() {
@@ -14,6 +14,33 @@
 //!     println!("{:?}", protocol_info);
 //! }
 //! ```
+//!
+//! ## Unmodelled fields
+//!
+//! `ProtocolInfo`, `Token`, `Whirlpool` and `SimpleTokenInfo` keep any JSON fields this crate
+//! does not model yet in their `extra` map, so data added to the API is not lost before the
+//! crate catches up.
+//!
+//! To detect changes to the API's shape instead, enable the `strict-schema` feature: any
+//! field the models do not know about then fails deserialization, naming the fields.
+//!
+//! ## WebAssembly
+//!
+//! The async client builds for `wasm32-unknown-unknown` with the `wasm` feature:
+//!
+//! ```text
+//! cargo build --target wasm32-unknown-unknown --features wasm
+//! ```
+//!
+//! Requests then go through the browser's `fetch` and timers through `setTimeout`, so no
+//! tokio runtime is needed: drive the futures with `wasm_bindgen_futures::spawn_local`. The
+//! `blocking` and `record-replay` features need a native target, and `with_proxy` is not
+//! available; the browser applies its own proxy settings and compression.
+
+#[cfg(all(target_arch = "wasm32", not(feature = "wasm")))]
+compile_error!("building for wasm32 requires the `wasm` feature");
 
 pub mod client;
+pub mod error;
+pub mod math;
 pub mod models;
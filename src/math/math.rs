@@ -0,0 +1,193 @@
+//! Concentrated-liquidity math matching the Whirlpool program's conventions.
+//!
+//! A tick `i` corresponds to a raw price of `1.0001^i` (token B per token A in raw token
+//! units), and sqrt prices are Q64.64 fixed-point integers.
+
+use crate::error::error::OrcaError;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::{Decimal, MathematicalOps};
+
+/// The lowest tick index supported by the Whirlpool program.
+pub const MIN_TICK_INDEX: i32 = -443636;
+/// The highest tick index supported by the Whirlpool program.
+pub const MAX_TICK_INDEX: i32 = 443636;
+/// The Q64.64 sqrt price at `MIN_TICK_INDEX`.
+pub const MIN_SQRT_PRICE_X64: u128 = 4295048016;
+/// The Q64.64 sqrt price at `MAX_TICK_INDEX`.
+pub const MAX_SQRT_PRICE_X64: u128 = 79226673515401279992447579055;
+
+/// `floor(sqrt(1.0001)^(2^i) * 2^96)`, used for positive ticks.
+const POSITIVE_TICK_FACTORS: [u128; 18] = [
+    79236085330515764027303304731,
+    79244008939048815603706035061,
+    79259858533276714757314932305,
+    79291567232598584799939703904,
+    79355022692464371645785046466,
+    79482085999252804386437311141,
+    79736823300114093921829183326,
+    80248749790819932309965073892,
+    81282483887344747381513967011,
+    83390072131320151908154831281,
+    87770609709833776024991924138,
+    97234110755111693312479820773,
+    119332217159966728226237229890,
+    179736315981702064433883588727,
+    407748233172238350107850275304,
+    2098478828474011932436660412517,
+    55581415166113811149459800483533,
+    38992368544603139932233054999993551,
+];
+
+/// `floor(sqrt(1.0001)^-(2^i) * 2^64)`, used for negative ticks.
+const NEGATIVE_TICK_FACTORS: [u128; 18] = [
+    18444899583751176498,
+    18443055278223354162,
+    18439367220385604838,
+    18431993317065449817,
+    18417254355718160513,
+    18387811781193591352,
+    18329067761203520168,
+    18212142134806087854,
+    17980523815641551639,
+    17526086738831147013,
+    16651378430235024244,
+    15030750278693429944,
+    12247334978882834399,
+    8131365268884726200,
+    3584323654723342297,
+    696457651847595233,
+    26294789957452057,
+    37481735321082,
+];
+
+/// Returns the Q64.64 sqrt price at `tick`, bit-for-bit as computed by the Whirlpool program.
+///
+/// `tick` is clamped to `[MIN_TICK_INDEX, MAX_TICK_INDEX]`.
+pub fn tick_to_sqrt_price(tick: i32) -> u128 {
+    let tick = tick.clamp(MIN_TICK_INDEX, MAX_TICK_INDEX);
+    let abs_tick = tick.unsigned_abs();
+    if tick >= 0 {
+        let mut ratio: u128 = if abs_tick & 1 != 0 {
+            79232123823359799118286999567
+        } else {
+            79228162514264337593543950336
+        };
+        for (bit, factor) in POSITIVE_TICK_FACTORS.iter().enumerate() {
+            if abs_tick & (2 << bit) != 0 {
+                ratio = mul_shift_96(ratio, *factor);
+            }
+        }
+        ratio >> 32
+    } else {
+        let mut ratio: u128 = if abs_tick & 1 != 0 {
+            18445821805675392311
+        } else {
+            18446744073709551616
+        };
+        for (bit, factor) in NEGATIVE_TICK_FACTORS.iter().enumerate() {
+            if abs_tick & (2 << bit) != 0 {
+                ratio = (ratio * factor) >> 64;
+            }
+        }
+        ratio
+    }
+}
+
+/// Returns the tick whose price range contains `price`, the UI price of token A in token B.
+///
+/// `price` is converted to a raw price with `price / 10^(decimals_a - decimals_b)` and the
+/// result is `floor(log_1.0001(raw_price))`, clamped to `[MIN_TICK_INDEX, MAX_TICK_INDEX]`.
+/// Non-positive prices map to `MIN_TICK_INDEX`.
+pub fn price_to_tick(price: Decimal, decimals_a: u8, decimals_b: u8) -> i32 {
+    if price <= Decimal::ZERO {
+        return MIN_TICK_INDEX;
+    }
+    let Some(raw_price) = shift_decimals(price, decimals_b, decimals_a) else {
+        return MAX_TICK_INDEX;
+    };
+    let Some(target) = raw_price
+        .sqrt()
+        .and_then(|root| root.checked_mul(Decimal::from(1u128 << 64)))
+        .and_then(|sqrt_price| sqrt_price.to_u128())
+    else {
+        return MAX_TICK_INDEX;
+    };
+
+    // Estimate with floating point, then correct against the exact integer sqrt prices.
+    let estimate = raw_price.to_f64().unwrap_or(1.0).ln() / 1.0001f64.ln();
+    let mut tick = (estimate.floor() as i32).clamp(MIN_TICK_INDEX, MAX_TICK_INDEX);
+    while tick > MIN_TICK_INDEX && tick_to_sqrt_price(tick) > target {
+        tick -= 1;
+    }
+    while tick < MAX_TICK_INDEX && tick_to_sqrt_price(tick + 1) <= target {
+        tick += 1;
+    }
+    tick
+}
+
+/// Returns the UI price of token A in token B at `tick`.
+pub fn tick_to_price(tick: i32, decimals_a: u8, decimals_b: u8) -> Result<Decimal, OrcaError> {
+    let raw_price = Decimal::new(10001, 4)
+        .checked_powi(i64::from(tick))
+        .ok_or(OrcaError::Overflow("tick price"))?;
+    shift_decimals(raw_price, decimals_a, decimals_b).ok_or(OrcaError::Overflow("tick price"))
+}
+
+/// Multiplies `value` by `10^(from - to)`, returning `None` on overflow.
+pub(crate) fn shift_decimals(value: Decimal, from: u8, to: u8) -> Option<Decimal> {
+    let scale = Decimal::from(10u64.checked_pow(u32::from(from.abs_diff(to)))?);
+    if from >= to {
+        value.checked_mul(scale)
+    } else {
+        value.checked_div(scale)
+    }
+}
+
+/// Returns `(a * b) >> 96`, computing the intermediate product with 256 bits.
+fn mul_shift_96(a: u128, b: u128) -> u128 {
+    const MASK: u128 = u64::MAX as u128;
+    let (a_hi, a_lo) = (a >> 64, a & MASK);
+    let (b_hi, b_lo) = (b >> 64, b & MASK);
+
+    let lo_lo = a_lo * b_lo;
+    let hi_lo = a_hi * b_lo;
+    let lo_hi = a_lo * b_hi;
+    let hi_hi = a_hi * b_hi;
+
+    let mid = (lo_lo >> 64) + (hi_lo & MASK) + (lo_hi & MASK);
+    let low = ((mid & MASK) << 64) | (lo_lo & MASK);
+    let high = hi_hi + (hi_lo >> 64) + (lo_hi >> 64) + (mid >> 64);
+    (high << 32) | (low >> 96)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_tick_to_sqrt_price_known_values() {
+        assert_eq!(tick_to_sqrt_price(0), 1 << 64);
+        assert_eq!(tick_to_sqrt_price(MIN_TICK_INDEX), MIN_SQRT_PRICE_X64);
+        assert_eq!(tick_to_sqrt_price(MAX_TICK_INDEX), MAX_SQRT_PRICE_X64);
+        assert_eq!(tick_to_sqrt_price(1), 18447666387855959850);
+        assert_eq!(tick_to_sqrt_price(-1), 18445821805675392311);
+    }
+
+    #[test]
+    fn test_price_to_tick() {
+        assert_eq!(price_to_tick(Decimal::ONE, 6, 6), 0);
+        // SOL/USDC at ~135.37 USDC per SOL.
+        let price = Decimal::from_str("135.3657").unwrap();
+        assert_eq!(price_to_tick(price, 9, 6), -19999);
+        assert_eq!(price_to_tick(Decimal::ZERO, 9, 6), MIN_TICK_INDEX);
+    }
+
+    #[test]
+    fn test_price_to_tick_round_trips_tick_prices() {
+        for tick in [-50000, -19999, -1, 0, 1, 64, 25000] {
+            let price = tick_to_price(tick, 9, 6).unwrap();
+            assert_eq!(price_to_tick(price, 9, 6), tick, "tick {tick}");
+        }
+    }
+}
@@ -0,0 +1,301 @@
+//! Shared JSON fixtures for unit tests.
+
+use serde_json::{json, Value};
+
+pub const SOL_USDC_POOL: &str = "Czfq3xZZDmsdGdUyrNLtRhGc47cXcZtLG4crryfu44zE";
+pub const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+pub const ORCA_MINT: &str = "orcaEKTdK7LKz57vaAYr9QeNsVEPfiu6QeMU1kektZE";
+
+/// A SOL/USDC whirlpool as returned by the `/pools` endpoints.
+pub fn whirlpool_json() -> Value {
+    json!({
+        "address": SOL_USDC_POOL,
+        "feeGrowthGlobalA": "4321001234567890",
+        "feeGrowthGlobalB": "1234567890123",
+        "feeRate": 400,
+        "liquidity": "45412349301943",
+        "protocolFeeOwedA": "1000",
+        "protocolFeeOwedB": "2000",
+        "protocolFeeRate": 1300,
+        "rewardLastUpdatedTimestamp": "2025-05-09T00:04:50Z",
+        "sqrtPrice": "6786903707898418446",
+        "tickCurrentIndex": -19999,
+        "tickSpacing": 4,
+        "tickSpacingSeed": "[4,0]",
+        "tokenMintA": "So11111111111111111111111111111111111111112",
+        "tokenMintB": USDC_MINT,
+        "tokenVaultA": [],
+        "tokenVaultB": "2WLWEuKDgkDUccTpbwYp1GToYktiSB1cXvreHUwiSUVP",
+        "updatedAt": "2025-05-09T00:04:50.745163Z",
+        "updatedSlot": 338769284,
+        "whirlpoolBump": "[255]",
+        "whirlpoolsConfig": "2LecshUwdy9xi7meFgHtFJQNSKk4KdTrcpvaB56dP2NQ",
+        "writeVersion": "1",
+        "adaptiveFee": null,
+        "adaptiveFeeEnabled": false,
+        "addressLookupTable": [],
+        "feeTierIndex": 4,
+        "hasWarning": false,
+        "lockedLiquidityPercent": null,
+        "poolType": "concentrated",
+        "price": "135.3657",
+        "rewards": [],
+        "stats": {
+            "24h": {
+                "fees": "120000.5",
+                "rewards": "0",
+                "volume": "300001250.0",
+                "yieldOverTvl": "0.0012"
+            }
+        },
+        "tokenA": {
+            "address": "So11111111111111111111111111111111111111112",
+            "decimals": 9,
+            "imageUrl": "https://example.com/sol.png",
+            "name": "Wrapped SOL",
+            "programId": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+            "symbol": "SOL",
+            "tags": "[]"
+        },
+        "tokenB": {
+            "address": USDC_MINT,
+            "decimals": 6,
+            "imageUrl": "https://example.com/usdc.png",
+            "name": "USD Coin",
+            "programId": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+            "symbol": "USDC",
+            "tags": "[]"
+        },
+        "tokenBalanceA": "550000.123456789",
+        "tokenBalanceB": "74500000.123456",
+        "tradeEnableTimestamp": "0",
+        "tvlUsdc": "100000000.0",
+        "yieldOverTvl": "0.0012"
+    })
+}
+
+pub const ECLIPSE_ETH_USDC_POOL: &str = "44w4HrojzxKwxEb3bmjRNcJ4irFhUGBUjrCYecYhPvqq";
+pub const ECLIPSE_USDC_MINT: &str = "AKEWE7Bgh87GPp171b4cJPSSZfmZwQ3KaqYqXoKLNAEE";
+
+/// An ETH/USDC whirlpool as returned by the Eclipse `/pools` endpoints.
+///
+/// Eclipse uses ETH as its native token, under the same wrapped-native mint as SOL on Solana,
+/// and its USDC is a Token-2022 mint.
+pub fn eclipse_pool_json() -> Value {
+    json!({
+        "address": ECLIPSE_ETH_USDC_POOL,
+        "feeGrowthGlobalA": "98765432101234",
+        "feeGrowthGlobalB": "246813579024680",
+        "feeRate": 400,
+        "liquidity": "2805146719384",
+        "protocolFeeOwedA": "15400",
+        "protocolFeeOwedB": "38820",
+        "protocolFeeRate": 1300,
+        "rewardLastUpdatedTimestamp": "2025-05-08T23:58:12Z",
+        "sqrtPrice": "29268304292085472758",
+        "tickCurrentIndex": 9232,
+        "tickSpacing": 4,
+        "tickSpacingSeed": "[4,0]",
+        "tokenMintA": "So11111111111111111111111111111111111111112",
+        "tokenMintB": ECLIPSE_USDC_MINT,
+        "tokenVaultA": [],
+        "tokenVaultB": "7nTQbhWwzX8M8Bw9fQHj5NgRR8vQ3LLq9nS5q3W1bqKE",
+        "updatedAt": "2025-05-09T00:01:37.118402Z",
+        "updatedSlot": 51304127,
+        "whirlpoolBump": "[254]",
+        "whirlpoolsConfig": "FVG4oDbGv16hqTUbovjyGmtYikn6UBEnazz6RVDMEFwv",
+        "writeVersion": "1",
+        "adaptiveFee": null,
+        "adaptiveFeeEnabled": false,
+        "addressLookupTable": [],
+        "feeTierIndex": 4,
+        "hasWarning": false,
+        "lockedLiquidityPercent": null,
+        "poolType": "concentrated",
+        "price": "2517.42",
+        "rewards": [],
+        "stats": {
+            "24h": {
+                "fees": "1874.31",
+                "rewards": "0",
+                "volume": "4685775.0",
+                "yieldOverTvl": "0.0003"
+            }
+        },
+        "tokenA": {
+            "address": "So11111111111111111111111111111111111111112",
+            "decimals": 9,
+            "imageUrl": "https://example.com/eth.png",
+            "name": "Ether",
+            "programId": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+            "symbol": "ETH",
+            "tags": "[]"
+        },
+        "tokenB": {
+            "address": ECLIPSE_USDC_MINT,
+            "decimals": 6,
+            "imageUrl": "https://example.com/usdc.png",
+            "name": "USD Coin",
+            "programId": "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb",
+            "symbol": "USDC",
+            "tags": "[]"
+        },
+        "tokenBalanceA": "1250.5",
+        "tokenBalanceB": "3150000.25",
+        "tradeEnableTimestamp": "0",
+        "tvlUsdc": "6298000.0",
+        "yieldOverTvl": "0.0003"
+    })
+}
+
+/// `whirlpool_json` with integer fields flipped between JSON numbers and quoted strings, as
+/// the API occasionally serves them.
+pub fn drifted_whirlpool_json() -> Value {
+    merge(
+        whirlpool_json(),
+        json!({
+            "feeRate": "400",
+            "liquidity": 45412349301943u64,
+            "tickCurrentIndex": "-19999",
+            "updatedSlot": "338769284",
+            "tokenA": { "decimals": "9" }
+        }),
+    )
+}
+
+/// `whirlpool_json` with `liquidity` and `sqrtPrice` written in scientific notation.
+pub fn scientific_whirlpool_json() -> Value {
+    merge(
+        whirlpool_json(),
+        json!({
+            "liquidity": "1.23e+21",
+            "sqrtPrice": "6.786903707898418446E18"
+        }),
+    )
+}
+
+/// A newly launched splash pool, with collections sent as `null` and optional fields omitted.
+pub fn splash_pool_json() -> Value {
+    let mut pool = whirlpool_json();
+    let fields = pool.as_object_mut().unwrap();
+    for key in [
+        "adaptiveFee",
+        "adaptiveFeeEnabled",
+        "addressLookupTable",
+        "hasWarning",
+        "lockedLiquidityPercent",
+    ] {
+        fields.remove(key);
+    }
+    for key in ["rewards", "stats", "tokenVaultA"] {
+        fields.insert(key.to_string(), Value::Null);
+    }
+    fields.insert("poolType".to_string(), json!("splash"));
+    fields.insert("tickSpacing".to_string(), json!(32896));
+    fields.insert("feeTierIndex".to_string(), json!(32896));
+    fields.insert("feeRate".to_string(), json!(10000));
+    pool
+}
+
+/// The token summary embedded in a whirlpool, tagged with `tags`.
+pub fn simple_token_json(address: &str, tags: &[&str]) -> Value {
+    json!({
+        "address": address,
+        "decimals": 6,
+        "imageUrl": "https://example.com/token.png",
+        "name": "Example Token",
+        "programId": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+        "symbol": "EXMPL",
+        "tags": json!(tags).to_string()
+    })
+}
+
+/// A token as returned by the `/tokens` endpoints.
+pub fn token_json(address: &str) -> Value {
+    json!({
+        "address": address,
+        "decimals": 9,
+        "extensions": "{}",
+        "freezeAuthority": null,
+        "isInitialized": true,
+        "metadata": "{}",
+        "mintAuthority": null,
+        "priceUsdc": "130.0",
+        "stats": "{}",
+        "supply": "1000000000",
+        "tags": "[]",
+        "tokenProgram": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+        "updatedAt": "2025-05-09T00:04:50.745163Z",
+        "updatedEpoch": 784
+    })
+}
+
+/// Applies `overrides` on top of `base`, merging nested objects key by key.
+pub fn merge(mut base: Value, overrides: Value) -> Value {
+    if let (Some(base_map), Value::Object(overrides)) = (base.as_object_mut(), overrides) {
+        for (key, value) in overrides {
+            match base_map.get_mut(&key) {
+                Some(existing) if existing.is_object() && value.is_object() => {
+                    *existing = merge(existing.take(), value);
+                }
+                _ => {
+                    base_map.insert(key, value);
+                }
+            }
+        }
+    }
+    base
+}
+
+/// Wraps `data` in the paginated envelope used by list endpoints.
+pub fn paginated(data: Vec<Value>) -> String {
+    json!({ "data": data, "meta": { "next": null, "previous": null } }).to_string()
+}
+
+/// The `adaptiveFee` block of a pool with adaptive fees enabled.
+pub fn adaptive_fee_json() -> Value {
+    json!({
+        "constants": {
+            "adaptiveFeeControlFactor": 5000,
+            "decayPeriod": 600,
+            "filterPeriod": 30,
+            "majorSwapThresholdTicks": 16,
+            "maxVolatilityAccumulator": 350000,
+            "reductionFactor": 5000,
+            "tickGroupSize": 16
+        },
+        "currentRate": 5000,
+        "maxRate": 100000,
+        "variables": {
+            "lastMajorSwapTimestamp": "1746749090",
+            "lastReferenceUpdateTimestamp": "1746749090",
+            "tickGroupIndexReference": -1250,
+            "volatilityAccumulator": 175000,
+            "volatilityReference": 20000
+        }
+    })
+}
+
+/// The 24h stats of `whirlpool_json` with a `txCount` metric the models do not know about.
+pub fn extended_pool_stats_json() -> Value {
+    json!({
+        "fees": "120000.5",
+        "rewards": "0",
+        "volume": "300001250.0",
+        "yieldOverTvl": "0.0012",
+        "txCount": 15230
+    })
+}
+
+/// An active reward emitting `emissions_per_second` tokens of `mint`.
+pub fn reward_json(mint: &str, emissions_per_second: &str) -> Value {
+    json!({
+        "authority": "DjDsi34mSB66p2nhBL6YvhbcLtZbkGfNybFeLDjJqxJW",
+        "emissions_per_second_x64": "0",
+        "growth_global_x64": "0",
+        "mint": mint,
+        "vault": "2tU3tKvj7RBxEatryyMYTUxBoLSSWCQXsdv1X6yce4T2",
+        "active": true,
+        "emissionsPerSecond": emissions_per_second
+    })
+}
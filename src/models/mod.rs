@@ -1 +1,5 @@
-pub mod models;
\ No newline at end of file
+#[allow(clippy::module_inception)]
+pub mod models;
+
+#[cfg(test)]
+pub(crate) mod fixtures;
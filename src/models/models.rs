@@ -1,20 +1,409 @@
+use crate::error::error::OrcaError;
+use crate::math::math::{shift_decimals, tick_to_price};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use std::cmp::{Ordering, Reverse};
 use std::collections::HashMap;
+use std::ops::Deref;
+use std::str::FromStr;
+
+/// The mint address of wrapped SOL, the SPL token representation of native SOL.
+pub const WSOL_MINT: &str = "So11111111111111111111111111111111111111112";
+
+/// Tag attached to tokens that have been verified.
+const VERIFIED_TAG: &str = "verified";
+
+/// The all-zero public key in base58.
+const DEFAULT_PUBKEY: &str = "11111111111111111111111111111111";
+
+/// The address of the Whirlpool program.
+pub const WHIRLPOOL_PROGRAM_ID: &str = "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc";
+
+/// Denominator of `Whirlpool::protocol_fee_rate`, which is expressed in basis points of the
+/// trading fee.
+pub const PROTOCOL_FEE_RATE_DENOMINATOR: u32 = 10_000;
+
+/// Denominator of `Whirlpool::fee_rate`, which is expressed in hundredths of a basis point.
+pub const FEE_RATE_DENOMINATOR: u32 = 1_000_000;
+
+/// The TVL, in USDC, at which `Whirlpool::lp_opportunity_score` halves a pool's APR.
+pub const LP_SCORE_TVL_SCALE: u32 = 100_000;
+
+/// Parses a decimal string field, reporting the field name on failure.
+///
+/// Scientific notation such as `"1.23e+21"` is accepted. Non-finite spellings such as
+/// `"NaN"` or `"Infinity"` are always rejected. With the `strict-numeric` feature, values
+/// that cannot be represented exactly (more than 28 significant digits) are rejected too
+/// instead of being rounded.
+pub(crate) fn parse_decimal(field: &'static str, value: &str) -> Result<Decimal, OrcaError> {
+    let invalid = || OrcaError::InvalidNumber {
+        field,
+        value: value.to_string(),
+    };
+    let expanded = expand_scientific(value);
+    let plain = expanded.as_deref().unwrap_or(value);
+    decimal_from_plain(plain).map_err(|_| invalid())
+}
+
+/// Parses a decimal string without exponent, exactly with the `strict-numeric` feature and
+/// rounding to 28 significant digits without it.
+fn decimal_from_plain(plain: &str) -> Result<Decimal, rust_decimal::Error> {
+    if cfg!(feature = "strict-numeric") {
+        Decimal::from_str_exact(plain)
+    } else {
+        Decimal::from_str(plain)
+    }
+}
+
+/// Parses an unsigned integer string field, reporting the field name on failure.
+///
+/// Scientific notation is accepted when it denotes a whole number, e.g. `"1.23e+21"` but not
+/// `"1.5e0"`. A value that looks like a rounded `f64`, such as `"7.922816251426434e+28"`, is
+/// rejected: see `is_rounded_float`.
+pub(crate) fn parse_u128(field: &'static str, value: &str) -> Result<u128, OrcaError> {
+    let invalid = || OrcaError::InvalidNumber {
+        field,
+        value: value.to_string(),
+    };
+    let expanded = expand_scientific(value);
+    if let Some(expanded) = &expanded {
+        if is_rounded_float(value, expanded) {
+            return Err(invalid());
+        }
+    }
+    let plain = expanded.as_deref().unwrap_or(value);
+    plain.parse().map_err(|_| invalid())
+}
+
+/// The number of significant digits from which a mantissa is taken to be a printed `f64`.
+///
+/// Shortest round-trip formatting writes an `f64` with up to 17 significant digits, and
+/// usually 15 or more for a large integer that did not start out round.
+const FLOAT_MANTISSA_DIGITS: usize = 15;
+
+/// Returns `true` if `value`, in scientific notation, has a mantissa as long as a printed
+/// `f64`'s but too short to spell out `expanded`, i.e. its last digits were rounded away and
+/// zero-padded back. Short mantissas such as `1.23e+21` are taken to be exact.
+fn is_rounded_float(value: &str, expanded: &str) -> bool {
+    let mantissa = value.trim().split(['e', 'E']).next().unwrap_or_default();
+    let significant = mantissa
+        .bytes()
+        .filter(u8::is_ascii_digit)
+        .skip_while(|&digit| digit == b'0')
+        .count();
+    let integer_digits = expanded.trim_start_matches('-').len();
+    significant >= FLOAT_MANTISSA_DIGITS && integer_digits > significant
+}
+
+/// The largest exponent `expand_scientific` expands; anything beyond overflows every numeric
+/// type the models use.
+const MAX_SCIENTIFIC_EXPONENT: i64 = 1_000;
+
+/// Rewrites a number in scientific notation, e.g. `"-1.5E3"`, in plain positional form
+/// (`"-1500"`), without trailing fractional zeros.
+///
+/// Returns `None` if `value` is not in scientific notation.
+fn expand_scientific(value: &str) -> Option<String> {
+    let (mantissa, exponent) = value.trim().split_once(['e', 'E'])?;
+    let exponent: i64 = exponent.parse().ok()?;
+    if exponent.abs() > MAX_SCIENTIFIC_EXPONENT {
+        return None;
+    }
+    let (negative, mantissa) = match mantissa.strip_prefix('-') {
+        Some(mantissa) => (true, mantissa),
+        None => (false, mantissa.strip_prefix('+').unwrap_or(mantissa)),
+    };
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits = format!("{integer}{fraction}");
+    if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    let point = integer.len() as i64 + exponent;
+    let (integer, fraction) = if point <= 0 {
+        (
+            "0".to_string(),
+            format!("{}{digits}", "0".repeat(-point as usize)),
+        )
+    } else if point as usize >= digits.len() {
+        let zeros = "0".repeat(point as usize - digits.len());
+        (format!("{digits}{zeros}"), String::new())
+    } else {
+        let (integer, fraction) = digits.split_at(point as usize);
+        (integer.to_string(), fraction.to_string())
+    };
+    let integer = match integer.trim_start_matches('0') {
+        "" => "0",
+        integer => integer,
+    };
+    let fraction = fraction.trim_end_matches('0');
+    let sign = if negative { "-" } else { "" };
+    Some(if fraction.is_empty() {
+        format!("{sign}{integer}")
+    } else {
+        format!("{sign}{integer}.{fraction}")
+    })
+}
+
+/// Converts a rate over `denominator` to a percentage.
+fn rate_percent(rate: u32, denominator: u32) -> Decimal {
+    Decimal::from(rate) * Decimal::ONE_HUNDRED / Decimal::from(denominator)
+}
+
+/// Converts a rate over `denominator` to basis points.
+fn rate_bps(rate: u32, denominator: u32) -> Decimal {
+    Decimal::from(rate) * Decimal::from(10_000) / Decimal::from(denominator)
+}
+
+/// A base58-encoded Solana public key.
+///
+/// By default any string is accepted. With the `validate-pubkeys` feature, deserialization
+/// fails unless the value decodes to exactly 32 bytes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Pubkey(String);
+
+impl Pubkey {
+    /// Wraps `value`, checking it is a valid public key when `validate-pubkeys` is enabled.
+    pub fn new(value: impl Into<String>) -> Result<Self, OrcaError> {
+        let value = value.into();
+        #[cfg(feature = "validate-pubkeys")]
+        {
+            let decoded = bs58::decode(&value).into_vec();
+            if !matches!(decoded, Ok(bytes) if bytes.len() == 32) {
+                return Err(OrcaError::InvalidPubkey {
+                    field: "pubkey",
+                    value,
+                });
+            }
+        }
+        Ok(Self(value))
+    }
+
+    /// Encodes raw public key bytes as base58.
+    pub fn from_bytes(bytes: &[u8; 32]) -> Self {
+        Self(bs58::encode(bytes).into_string())
+    }
+
+    /// Returns the base58 string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns `true` for the all-zero key, which the program uses to mark unset addresses.
+    pub fn is_default(&self) -> bool {
+        self.0 == DEFAULT_PUBKEY
+    }
+
+    /// Consumes the key, returning the base58 string.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl Deref for Pubkey {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Pubkey {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for Pubkey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for Pubkey {
+    type Err = OrcaError;
+
+    fn from_str(value: &str) -> Result<Self, OrcaError> {
+        Pubkey::new(value)
+    }
+}
+
+impl PartialEq<str> for Pubkey {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for Pubkey {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<String> for Pubkey {
+    fn eq(&self, other: &String) -> bool {
+        &self.0 == other
+    }
+}
+
+impl<'de> Deserialize<'de> for Pubkey {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        Pubkey::new(value).map_err(serde::de::Error::custom)
+    }
+}
 
 /// Protocol information including TVL, volume, fees, and revenue
 #[derive(Debug, Deserialize)]
 pub struct ProtocolInfo {
-    #[serde(rename = "fees24hUsdc")]
+    #[serde(rename = "fees24hUsdc", deserialize_with = "string_or_number")]
     pub fees_24h_usdc: String,
-    #[serde(rename = "revenue24hUsdc")]
+    #[serde(rename = "revenue24hUsdc", deserialize_with = "string_or_number")]
     pub revenue_24h_usdc: String,
+    #[serde(deserialize_with = "string_or_number")]
     pub tvl: String,
-    #[serde(rename = "volume24hUsdc")]
+    #[serde(rename = "volume24hUsdc", deserialize_with = "string_or_number")]
     pub volume_24h_usdc: String,
+    /// Fields returned by the API that this struct does not model yet.
+    #[serde(flatten, deserialize_with = "deserialize_extra")]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl ProtocolInfo {
+    /// Computes how TVL, 24h volume, 24h fees and 24h revenue changed since `previous`.
+    pub fn delta(&self, previous: &ProtocolInfo) -> Result<ProtocolDelta, OrcaError> {
+        let change = |field: &'static str, current: &str, previous: &str| {
+            ValueChange::between(
+                parse_decimal(field, previous)?,
+                parse_decimal(field, current)?,
+            )
+        };
+        Ok(ProtocolDelta {
+            tvl: change("tvl", &self.tvl, &previous.tvl)?,
+            volume_24h_usdc: change(
+                "volume_24h_usdc",
+                &self.volume_24h_usdc,
+                &previous.volume_24h_usdc,
+            )?,
+            fees_24h_usdc: change(
+                "fees_24h_usdc",
+                &self.fees_24h_usdc,
+                &previous.fees_24h_usdc,
+            )?,
+            revenue_24h_usdc: change(
+                "revenue_24h_usdc",
+                &self.revenue_24h_usdc,
+                &previous.revenue_24h_usdc,
+            )?,
+        })
+    }
+}
+
+impl std::fmt::Display for ProtocolInfo {
+    /// Formats a one-line summary, e.g. `TVL $230.6M vol24h $552.6M fees24h $317.4k
+    /// revenue24h $41.3k`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "TVL {} vol24h {} fees24h {} revenue24h {}",
+            UsdAmount(&self.tvl),
+            UsdAmount(&self.volume_24h_usdc),
+            UsdAmount(&self.fees_24h_usdc),
+            UsdAmount(&self.revenue_24h_usdc)
+        )
+    }
+}
+
+/// The change in protocol-wide metrics between two `ProtocolInfo` snapshots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProtocolDelta {
+    pub tvl: ValueChange,
+    pub volume_24h_usdc: ValueChange,
+    pub fees_24h_usdc: ValueChange,
+    pub revenue_24h_usdc: ValueChange,
+}
+
+/// The change of a single value between two snapshots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValueChange {
+    /// `current - previous`.
+    pub absolute: Decimal,
+    /// The change as a percentage of the previous value, or `None` if it was zero.
+    pub percent: Option<Decimal>,
+}
+
+impl ValueChange {
+    fn between(previous: Decimal, current: Decimal) -> Result<Self, OrcaError> {
+        let overflow = || OrcaError::Overflow("ValueChange");
+        let absolute = current.checked_sub(previous).ok_or_else(overflow)?;
+        let percent = if previous.is_zero() {
+            None
+        } else {
+            let ratio = absolute.checked_div(previous).ok_or_else(overflow)?;
+            Some(
+                ratio
+                    .checked_mul(Decimal::ONE_HUNDRED)
+                    .ok_or_else(overflow)?,
+            )
+        };
+        Ok(Self { absolute, percent })
+    }
+}
+
+/// A field's value in an older and a newer snapshot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldChange<T> {
+    pub old: T,
+    pub new: T,
+}
+
+impl<T: PartialEq> FieldChange<T> {
+    /// Returns the change if `old` and `new` differ.
+    fn between(old: T, new: T) -> Option<Self> {
+        (old != new).then_some(Self { old, new })
+    }
+}
+
+/// The fields that changed between two snapshots of the same pool, returned by
+/// `Whirlpool::diff`. A field that did not change is `None`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WhirlpoolDiff {
+    pub price: Option<FieldChange<String>>,
+    pub sqrt_price: Option<FieldChange<String>>,
+    pub tick_current_index: Option<FieldChange<i32>>,
+    pub liquidity: Option<FieldChange<String>>,
+    pub tvl_usdc: Option<FieldChange<String>>,
+    pub fee_rate: Option<FieldChange<u32>>,
+    /// Mints of active rewards present only in the newer snapshot.
+    pub rewards_added: Vec<Pubkey>,
+    /// Mints of active rewards present only in the older snapshot.
+    pub rewards_removed: Vec<Pubkey>,
+}
+
+impl WhirlpoolDiff {
+    /// Returns `true` if none of the compared fields changed.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Returns the change between two numeric strings, comparing them as decimals when both
+/// parse so that `"1.0"` and `"1"` are equal.
+fn numeric_change(field: &'static str, old: &str, new: &str) -> Option<FieldChange<String>> {
+    if let (Ok(old), Ok(new)) = (parse_decimal(field, old), parse_decimal(field, new)) {
+        if old == new {
+            return None;
+        }
+    }
+    FieldChange::between(old.to_string(), new.to_string())
 }
 
 /// Statistics for a token.
 #[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct TokenStats {
     #[serde(rename = "24h")]
     pub h24: TokenVolume,
@@ -22,88 +411,409 @@ pub struct TokenStats {
 
 /// The volume of a token.
 #[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct TokenVolume {
+    #[serde(deserialize_with = "string_or_number")]
     pub volume: String,
 }
 
 /// Detailed information about the Orca token.
 #[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct TokenInfo {
-    #[serde(rename = "circulatingSupply")]
+    #[serde(rename = "circulatingSupply", deserialize_with = "string_or_number")]
     pub circulating_supply: String,
     pub description: String,
     #[serde(rename = "imageUrl")]
     pub image_url: String,
     pub name: String,
+    #[serde(deserialize_with = "string_or_number")]
     pub price: String,
     pub stats: TokenStats,
     pub symbol: String,
-    #[serde(rename = "totalSupply")]
+    #[serde(rename = "totalSupply", deserialize_with = "string_or_number")]
     pub total_supply: String,
 }
 
 /// The circulating supply of the Orca token.
 #[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct CirculatingSupplyResponse {
+    #[serde(deserialize_with = "string_or_number")]
     pub circulating_supply: String,
 }
 
 /// The total supply of the Orca token.
 #[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct TotalSupplyResponse {
+    #[serde(deserialize_with = "string_or_number")]
     pub total_supply: String,
 }
 
+/// The circulating and total supply of the Orca token, returned by
+/// `OrcaClient::get_token_supply`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenSupply {
+    pub circulating: Decimal,
+    pub total: Decimal,
+}
+
+impl TokenSupply {
+    /// Returns the circulating share of the total supply, from 0 to 1. Returns `None` if
+    /// the total supply is zero.
+    pub fn circulating_fraction(&self) -> Option<Decimal> {
+        self.circulating.checked_div(self.total)
+    }
+}
+
+/// The tokens and pools matching a query, returned by `OrcaClient::search`.
+#[derive(Debug)]
+pub struct SearchResults {
+    pub tokens: Vec<Token>,
+    pub pools: Vec<Whirlpool>,
+}
+
+impl SearchResults {
+    /// Returns `true` if neither tokens nor pools matched.
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty() && self.pools.is_empty()
+    }
+}
+
 /// A paginated response from the API.
 #[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct Paginated<T> {
     pub data: Vec<T>,
     pub meta: Meta,
 }
 
+impl<T> Paginated<T> {
+    /// Consumes the page and returns its first item.
+    ///
+    /// Returns `OrcaError::EmptyResult` if `data` is empty.
+    pub fn into_first(self) -> Result<T, OrcaError> {
+        self.data.into_iter().next().ok_or(OrcaError::EmptyResult)
+    }
+
+    /// Consumes the page of a single-item lookup and returns its item.
+    ///
+    /// Returns `OrcaError::NotFound` for `resource` `id` if `data` is empty.
+    pub fn into_found(self, resource: &'static str, id: &str) -> Result<T, OrcaError> {
+        self.data
+            .into_iter()
+            .next()
+            .ok_or_else(|| OrcaError::NotFound {
+                resource,
+                id: id.to_string(),
+            })
+    }
+
+    /// Returns the cursor for the next page, if there is one.
+    pub fn next_cursor(&self) -> Option<&Cursor> {
+        self.meta.next.as_ref()
+    }
+}
+
+impl<T> Deref for Paginated<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.data
+    }
+}
+
+impl<T> AsRef<[T]> for Paginated<T> {
+    fn as_ref(&self) -> &[T] {
+        &self.data
+    }
+}
+
+impl<T> IntoIterator for Paginated<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Paginated<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.iter()
+    }
+}
+
+/// An opaque pagination cursor, as found in `Meta::next` and `Meta::previous`.
+///
+/// Cursors serialize as plain strings, so they can be stored and passed back in a later run
+/// to resume a scan.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct Cursor(String);
+
+impl Cursor {
+    /// Returns the cursor string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Consumes the cursor and returns the string.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl From<String> for Cursor {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for Cursor {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+/// A Solana epoch number, as in `Token::updated_epoch`.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Deserialize, Serialize,
+)]
+#[serde(transparent)]
+pub struct Epoch(pub u64);
+
+impl From<u64> for Epoch {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Epoch> for u64 {
+    fn from(epoch: Epoch) -> Self {
+        epoch.0
+    }
+}
+
+impl std::fmt::Display for Epoch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A Solana slot number, as in `Whirlpool::updated_slot`.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Deserialize, Serialize,
+)]
+#[serde(transparent)]
+pub struct Slot(pub u64);
+
+impl From<u64> for Slot {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Slot> for u64 {
+    fn from(slot: Slot) -> Self {
+        slot.0
+    }
+}
+
+impl std::fmt::Display for Slot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 /// Metadata for a paginated response.
 #[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct Meta {
-    pub next: Option<String>,
-    pub previous: Option<String>,
+    pub next: Option<Cursor>,
+    pub previous: Option<Cursor>,
+    /// Total number of items matching the query, when reported.
+    #[serde(default)]
+    pub total: Option<u64>,
+    /// Number of items in this page, when reported.
+    #[serde(default)]
+    pub count: Option<u64>,
+    /// Page size limit applied to the query, when reported.
+    #[serde(default)]
+    pub limit: Option<u64>,
+}
+
+impl Meta {
+    /// Returns `true` if there is a page after this one.
+    pub fn has_next(&self) -> bool {
+        self.next.is_some()
+    }
+
+    /// Returns `true` if there is a page before this one.
+    pub fn has_previous(&self) -> bool {
+        self.previous.is_some()
+    }
 }
 
 /// Information about a token.
 #[derive(Debug, Deserialize)]
 pub struct Token {
-    pub address: String,
+    pub address: Pubkey,
+    #[serde(deserialize_with = "string_or_int")]
     pub decimals: u8,
     pub extensions: String, // todo: parse this string as json
     #[serde(rename = "freezeAuthority")]
-    pub freeze_authority: Option<String>,
+    pub freeze_authority: Option<Pubkey>,
     #[serde(rename = "isInitialized")]
     pub is_initialized: bool,
     pub metadata: String, // todo: parse this string as json
     #[serde(rename = "mintAuthority")]
-    pub mint_authority: Option<String>,
-    #[serde(rename = "priceUsdc")]
+    pub mint_authority: Option<Pubkey>,
+    #[serde(rename = "priceUsdc", deserialize_with = "string_or_number")]
     pub price_usdc: String,
     pub stats: String, // todo: parse this string as json
+    #[serde(deserialize_with = "string_or_number")]
     pub supply: String,
     pub tags: String, // todo: parse this string as json
     #[serde(rename = "tokenProgram")]
-    pub token_program: String,
-    #[serde(rename = "updatedAt")]
-    pub updated_at: String,
-    #[serde(rename = "updatedEpoch")]
+    pub token_program: Pubkey,
+    #[serde(rename = "updatedAt", deserialize_with = "deserialize_timestamp")]
+    pub updated_at: DateTime<Utc>,
+    #[serde(rename = "updatedEpoch", deserialize_with = "string_or_int")]
     pub updated_epoch: u64,
+    /// Fields returned by the API that this struct does not model yet.
+    #[serde(flatten, deserialize_with = "deserialize_extra")]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl Token {
+    /// Returns `true` if this token is wrapped SOL.
+    pub fn is_native_sol(&self) -> bool {
+        self.address == WSOL_MINT
+    }
+
+    /// Returns `updated_epoch` as an `Epoch`.
+    pub fn epoch(&self) -> Epoch {
+        Epoch(self.updated_epoch)
+    }
+
+    /// Returns `true` if the token was last updated before `current_epoch`.
+    pub fn is_stale(&self, current_epoch: Epoch) -> bool {
+        self.epoch() < current_epoch
+    }
+
+    /// Returns the token as the `SimpleTokenInfo` embedded in pools; see the `From` impl.
+    pub fn to_simple(&self) -> SimpleTokenInfo {
+        SimpleTokenInfo::from(self)
+    }
+
+    /// Parses the JSON-encoded `metadata` field.
+    pub fn parsed_metadata(&self) -> Result<TokenMetadata, OrcaError> {
+        Ok(serde_json::from_str(&self.metadata)?)
+    }
+}
+
+impl std::fmt::Display for Token {
+    /// Formats a one-line summary, e.g. `ORCA orcaEKTdK7LKz57vaAYr9QeNsVEPfiu6QeMU1kektZE
+    /// $1.85`. The symbol is omitted if the metadata does not provide one.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(symbol) = self.parsed_metadata().ok().and_then(|m| m.symbol) {
+            write!(f, "{} ", symbol)?;
+        }
+        write!(f, "{} {}", self.address, UsdAmount(&self.price_usdc))
+    }
+}
+
+/// Copies `address`, `decimals` and `tags`, and takes `program_id` from `token_program`.
+///
+/// `name`, `symbol` and `image_url` come from the token's `metadata`, and are empty when it
+/// does not provide them or does not parse. They can therefore differ from the values the
+/// API embeds in a pool, whose image URL in particular may point to another host. `extra`
+/// is left empty.
+impl From<&Token> for SimpleTokenInfo {
+    fn from(token: &Token) -> Self {
+        let metadata = token.parsed_metadata().unwrap_or_default();
+        SimpleTokenInfo {
+            address: token.address.clone(),
+            decimals: token.decimals,
+            image_url: metadata.image.unwrap_or_default(),
+            name: metadata.name.unwrap_or_default(),
+            program_id: token.token_program.clone(),
+            symbol: metadata.symbol.unwrap_or_default(),
+            tags: token.tags.clone(),
+            extra: HashMap::new(),
+        }
+    }
+}
+
+/// The token metadata embedded as a JSON string in `Token::metadata`.
+#[derive(Debug, Default, Deserialize)]
+pub struct TokenMetadata {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub symbol: Option<String>,
+    #[serde(default)]
+    pub image: Option<String>,
 }
 
 /// Information about locked liquidity.
 #[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct LockInfo {
-    #[serde(rename = "lockedPercentage")]
+    #[serde(rename = "lockedPercentage", deserialize_with = "string_or_number")]
     pub locked_percentage: String,
     pub name: String,
 }
 
+impl LockInfo {
+    /// Parses `locked_percentage`.
+    pub fn percentage_decimal(&self) -> Result<Decimal, OrcaError> {
+        parse_decimal("locked_percentage", &self.locked_percentage)
+    }
+}
+
+/// A chain served by the Orca API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Chain {
+    Solana,
+    Eclipse,
+}
+
+impl Chain {
+    /// Every supported chain.
+    pub const ALL: [Chain; 2] = [Chain::Solana, Chain::Eclipse];
+
+    /// Returns the path segment used for this chain in API URLs.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Chain::Solana => "solana",
+            Chain::Eclipse => "eclipse",
+        }
+    }
+
+    /// Returns the mint of the chain's wrapped native token: SOL on Solana, ETH on Eclipse.
+    ///
+    /// Both chains use the same wrapped-native mint address.
+    pub fn native_mint(&self) -> &'static str {
+        match self {
+            Chain::Solana | Chain::Eclipse => WSOL_MINT,
+        }
+    }
+}
+
+impl std::fmt::Display for Chain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// A time period for statistics.
-#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 pub enum TimePeriod {
     #[serde(rename = "5m")]
@@ -126,97 +836,1010 @@ pub enum TimePeriod {
     H24,
 }
 
+impl TimePeriod {
+    /// Every period, shortest first.
+    pub const ALL: [TimePeriod; 9] = [
+        TimePeriod::M5,
+        TimePeriod::M15,
+        TimePeriod::M30,
+        TimePeriod::H1,
+        TimePeriod::H2,
+        TimePeriod::H4,
+        TimePeriod::H8,
+        TimePeriod::H12,
+        TimePeriod::H24,
+    ];
+
+    /// Returns the API spelling of the period, e.g. `"24h"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TimePeriod::M5 => "5m",
+            TimePeriod::M15 => "15m",
+            TimePeriod::M30 => "30m",
+            TimePeriod::H1 => "1h",
+            TimePeriod::H2 => "2h",
+            TimePeriod::H4 => "4h",
+            TimePeriod::H8 => "8h",
+            TimePeriod::H12 => "12h",
+            TimePeriod::H24 => "24h",
+        }
+    }
+
+    /// Returns the length of the period in minutes.
+    pub fn minutes(&self) -> u32 {
+        match self {
+            TimePeriod::M5 => 5,
+            TimePeriod::M15 => 15,
+            TimePeriod::M30 => 30,
+            TimePeriod::H1 => 60,
+            TimePeriod::H2 => 120,
+            TimePeriod::H4 => 240,
+            TimePeriod::H8 => 480,
+            TimePeriod::H12 => 720,
+            TimePeriod::H24 => 1440,
+        }
+    }
+
+    /// Returns the value sent for the `stats` query parameter, e.g. `"24h"`.
+    pub fn as_query_value(&self) -> &'static str {
+        self.as_str()
+    }
+}
+
+impl std::fmt::Display for TimePeriod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TimePeriod {
+    type Err = OrcaError;
+
+    /// Parses the API spelling of a period, e.g. `"24h"`.
+    fn from_str(value: &str) -> Result<Self, OrcaError> {
+        TimePeriod::ALL
+            .into_iter()
+            .find(|period| period.as_str() == value)
+            .ok_or_else(|| {
+                let expected: Vec<_> = TimePeriod::ALL.iter().map(|p| p.as_str()).collect();
+                OrcaError::Validation(format!(
+                    "unknown time period {:?}, expected one of {}",
+                    value,
+                    expected.join(", ")
+                ))
+            })
+    }
+}
+
+/// The kind of a whirlpool.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PoolType {
+    /// A concentrated-liquidity pool.
+    Concentrated,
+    /// A splash pool: full-range liquidity for launching tokens.
+    Splash,
+    /// A pool type this crate does not know yet, holding the raw value.
+    Unknown(String),
+}
+
+impl PoolType {
+    /// Returns the value the API uses for this pool type.
+    pub fn as_str(&self) -> &str {
+        match self {
+            PoolType::Concentrated => "concentrated",
+            PoolType::Splash => "splash",
+            PoolType::Unknown(raw) => raw,
+        }
+    }
+}
+
+impl From<String> for PoolType {
+    fn from(raw: String) -> Self {
+        match raw.as_str() {
+            "concentrated" => PoolType::Concentrated,
+            "splash" => PoolType::Splash,
+            _ => PoolType::Unknown(raw),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for PoolType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        String::deserialize(deserializer).map(PoolType::from)
+    }
+}
+
 /// Information about a whirlpool.
 #[derive(Debug, Deserialize)]
 pub struct Whirlpool {
-    pub address: String,
+    pub address: Pubkey,
     #[serde(rename = "feeGrowthGlobalA")]
     pub fee_growth_global_a: String,
     #[serde(rename = "feeGrowthGlobalB")]
     pub fee_growth_global_b: String,
-    #[serde(rename = "feeRate")]
+    /// The swap fee over `FEE_RATE_DENOMINATOR`; see `fee_rate_percent`.
+    #[serde(rename = "feeRate", deserialize_with = "string_or_int")]
     pub fee_rate: u32,
+    #[serde(deserialize_with = "string_or_integer")]
     pub liquidity: String,
     #[serde(rename = "protocolFeeOwedA")]
     pub protocol_fee_owed_a: String,
     #[serde(rename = "protocolFeeOwedB")]
     pub protocol_fee_owed_b: String,
-    #[serde(rename = "protocolFeeRate")]
+    /// The protocol's share of the swap fee over `PROTOCOL_FEE_RATE_DENOMINATOR`.
+    #[serde(rename = "protocolFeeRate", deserialize_with = "string_or_int")]
     pub protocol_fee_rate: u32,
-    #[serde(rename = "rewardLastUpdatedTimestamp")]
-    pub reward_last_updated_timestamp: String,
-    #[serde(rename = "sqrtPrice")]
+    #[serde(
+        rename = "rewardLastUpdatedTimestamp",
+        deserialize_with = "deserialize_timestamp"
+    )]
+    pub reward_last_updated_timestamp: DateTime<Utc>,
+    #[serde(rename = "sqrtPrice", deserialize_with = "string_or_integer")]
     pub sqrt_price: String,
-    #[serde(rename = "tickCurrentIndex")]
+    #[serde(rename = "tickCurrentIndex", deserialize_with = "string_or_int")]
     pub tick_current_index: i32,
-    #[serde(rename = "tickSpacing")]
+    #[serde(rename = "tickSpacing", deserialize_with = "string_or_int")]
     pub tick_spacing: u16,
-    #[serde(rename = "tickSpacingSeed")]
-    pub tick_spacing_seed: String,
+    /// The tick spacing as two little-endian bytes, as used in PDA seeds; sent by the API as
+    /// a JSON-encoded byte array such as `"[4,0]"`. See `tick_spacing_seed_value`.
+    #[serde(rename = "tickSpacingSeed", deserialize_with = "deserialize_seed")]
+    pub tick_spacing_seed: [u8; 2],
     #[serde(rename = "tokenMintA")]
-    pub token_mint_a: String,
+    pub token_mint_a: Pubkey,
     #[serde(rename = "tokenMintB")]
-    pub token_mint_b: String,
-    #[serde(rename = "tokenVaultA")]
+    pub token_mint_b: Pubkey,
+    #[serde(rename = "tokenVaultA", default, deserialize_with = "null_as_default")]
     pub token_vault_a: Vec<u64>,
     #[serde(rename = "tokenVaultB")]
-    pub token_vault_b: String,
-    #[serde(rename = "updatedAt")]
-    pub updated_at: String,
-    #[serde(rename = "updatedSlot")]
+    pub token_vault_b: Pubkey,
+    #[serde(rename = "updatedAt", deserialize_with = "deserialize_timestamp")]
+    pub updated_at: DateTime<Utc>,
+    #[serde(rename = "updatedSlot", deserialize_with = "string_or_int")]
     pub updated_slot: u64,
-    #[serde(rename = "whirlpoolBump")]
-    pub whirlpool_bump: String,
+    /// The bump seed of the whirlpool PDA; sent by the API as a one-byte array such as
+    /// `"[255]"`.
+    #[serde(rename = "whirlpoolBump", deserialize_with = "deserialize_bump")]
+    pub whirlpool_bump: u8,
     #[serde(rename = "whirlpoolsConfig")]
-    pub whirlpools_config: String,
+    pub whirlpools_config: Pubkey,
     #[serde(rename = "writeVersion")]
     pub write_version: String,
-    #[serde(rename = "adaptiveFee")]
+    #[serde(rename = "adaptiveFee", default)]
     pub adaptive_fee: Option<AdaptiveFee>,
-    #[serde(rename = "adaptiveFeeEnabled")]
+    #[serde(
+        rename = "adaptiveFeeEnabled",
+        default,
+        deserialize_with = "null_as_default"
+    )]
     pub adaptive_fee_enabled: bool,
-    #[serde(rename = "addressLookupTable")]
+    #[serde(
+        rename = "addressLookupTable",
+        default,
+        deserialize_with = "null_as_default"
+    )]
     pub address_lookup_table: Vec<u64>,
-    #[serde(rename = "feeTierIndex")]
+    #[serde(rename = "feeTierIndex", deserialize_with = "string_or_int")]
     pub fee_tier_index: u32,
-    #[serde(rename = "hasWarning")]
+    #[serde(rename = "hasWarning", default, deserialize_with = "null_as_default")]
     pub has_warning: bool,
-    #[serde(rename = "lockedLiquidityPercent")]
+    #[serde(rename = "lockedLiquidityPercent", default)]
     pub locked_liquidity_percent: Option<Vec<LockInfo>>,
     #[serde(rename = "poolType")]
-    pub pool_type: String,
+    pub pool_type: PoolType,
+    #[serde(deserialize_with = "string_or_number")]
     pub price: String,
+    #[serde(default, deserialize_with = "null_as_default")]
     pub rewards: Vec<Reward>,
+    #[serde(default, deserialize_with = "deserialize_pool_stats")]
     pub stats: HashMap<TimePeriod, PoolStats>,
     #[serde(rename = "tokenA")]
     pub token_a: SimpleTokenInfo,
     #[serde(rename = "tokenB")]
     pub token_b: SimpleTokenInfo,
-    #[serde(rename = "tokenBalanceA")]
+    #[serde(rename = "tokenBalanceA", deserialize_with = "string_or_number")]
     pub token_balance_a: String,
-    #[serde(rename = "tokenBalanceB")]
+    #[serde(rename = "tokenBalanceB", deserialize_with = "string_or_number")]
     pub token_balance_b: String,
-    #[serde(rename = "tradeEnableTimestamp")]
-    pub trade_enable_timestamp: String,
-    #[serde(rename = "tvlUsdc")]
+    #[serde(
+        rename = "tradeEnableTimestamp",
+        deserialize_with = "deserialize_timestamp"
+    )]
+    pub trade_enable_timestamp: DateTime<Utc>,
+    #[serde(rename = "tvlUsdc", deserialize_with = "string_or_number")]
     pub tvl_usdc: String,
-    #[serde(rename = "yieldOverTvl")]
+    #[serde(rename = "yieldOverTvl", deserialize_with = "string_or_number")]
     pub yield_over_tvl: String,
+    /// Fields returned by the API that this struct does not model yet.
+    #[serde(flatten, deserialize_with = "deserialize_extra")]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl Whirlpool {
+    /// Returns `updated_slot` as a `Slot`.
+    pub fn slot(&self) -> Slot {
+        Slot(self.updated_slot)
+    }
+
+    /// Returns `true` if either side of the pool is wrapped SOL.
+    pub fn has_native_sol(&self) -> bool {
+        self.token_mint_a == WSOL_MINT || self.token_mint_b == WSOL_MINT
+    }
+
+    /// Returns `true` if either side of the pool is the wrapped native token of `chain`.
+    pub fn has_native_token(&self, chain: Chain) -> bool {
+        let mint = chain.native_mint();
+        self.token_mint_a == mint || self.token_mint_b == mint
+    }
+
+    /// Returns `token_vault_a` as a base58 public key.
+    ///
+    /// The array may hold the 32 key bytes, or four `u64` words holding them in little-endian
+    /// order. Any other shape, including an empty array, is an `OrcaError::InvalidPubkey`.
+    pub fn token_vault_a_pubkey(&self) -> Result<Pubkey, OrcaError> {
+        pubkey_from_words("token_vault_a", &self.token_vault_a)
+    }
+
+    /// Returns `address_lookup_table` as a base58 public key, in the same way as
+    /// `token_vault_a_pubkey`.
+    pub fn address_lookup_table_pubkey(&self) -> Result<Pubkey, OrcaError> {
+        pubkey_from_words("address_lookup_table", &self.address_lookup_table)
+    }
+
+    /// Returns `true` if the pool should be treated as risky.
+    ///
+    /// A pool is risky when the API flags it with `has_warning`, or when either token lacks
+    /// the `verified` tag (see `SimpleTokenInfo::is_verified`). Tags that fail to parse count
+    /// as unverified. Nothing else, such as TVL, volume or locked liquidity, is considered.
+    pub fn is_risky(&self) -> bool {
+        self.has_warning || !self.token_a.is_verified() || !self.token_b.is_verified()
+    }
+
+    /// Returns the yield over TVL earned by liquidity providers after the protocol fee.
+    ///
+    /// `yield_over_tvl` is gross: it counts every fee paid by traders. The protocol keeps
+    /// `protocol_fee_rate / 10_000` of each fee, so the LP share is
+    /// `yield_over_tvl * (1 - protocol_fee_rate / 10_000)`.
+    pub fn net_yield_over_tvl(&self) -> Result<Decimal, OrcaError> {
+        let gross = parse_decimal("yield_over_tvl", &self.yield_over_tvl)?;
+        let protocol_share =
+            Decimal::from(self.protocol_fee_rate) / Decimal::from(PROTOCOL_FEE_RATE_DENOMINATOR);
+        Ok(gross * (Decimal::ONE - protocol_share))
+    }
+
+    /// Parses `sqrt_price`, the square root of the price as a Q64.64 fixed-point integer,
+    /// accepting scientific notation.
+    pub fn sqrt_price_u128(&self) -> Result<u128, OrcaError> {
+        parse_u128("sqrt_price", &self.sqrt_price)
+    }
+
+    /// Parses `liquidity` as an unsigned integer, accepting scientific notation.
+    pub fn liquidity_u128(&self) -> Result<u128, OrcaError> {
+        parse_u128("liquidity", &self.liquidity)
+    }
+
+    /// Computes the price of token A in units of token B from `sqrt_price`.
+    ///
+    /// Uses the Whirlpool formula `price = sqrt_price^2 / 2^128 * 10^(decimals_a - decimals_b)`,
+    /// i.e. the Q64.64 square root is scaled down by `2^64`, squared, then adjusted from
+    /// raw token amounts to UI amounts. The result can be checked against `price`.
+    pub fn computed_price(&self, decimals_a: u8, decimals_b: u8) -> Result<Decimal, OrcaError> {
+        let overflow = || OrcaError::Overflow("computed_price");
+        let sqrt_price = i128::try_from(self.sqrt_price_u128()?).map_err(|_| overflow())?;
+        let sqrt_price =
+            Decimal::try_from_i128_with_scale(sqrt_price, 0).map_err(|_| overflow())?;
+        let root = sqrt_price / Decimal::from(1u128 << 64);
+        let raw_price = root.checked_mul(root).ok_or_else(overflow)?;
+        shift_decimals(raw_price, decimals_a, decimals_b).ok_or_else(overflow)
+    }
+
+    /// Returns the lower and upper price bounds of the initializable tick range containing
+    /// `tick_current_index`.
+    ///
+    /// The range starts at the current tick rounded down to a multiple of `tick_spacing` and
+    /// spans `tick_spacing` ticks. Prices are `1.0001^tick` adjusted for the decimals of
+    /// `token_a` and `token_b`.
+    pub fn current_tick_price_range(&self) -> Result<(Decimal, Decimal), OrcaError> {
+        if self.tick_spacing == 0 {
+            return Err(OrcaError::Validation("`tick_spacing` is zero".to_string()));
+        }
+        let spacing = i32::from(self.tick_spacing);
+        let lower_tick = self.tick_current_index.div_euclid(spacing) * spacing;
+        let upper_tick = lower_tick + spacing;
+        let (decimals_a, decimals_b) = (self.token_a.decimals, self.token_b.decimals);
+        Ok((
+            tick_to_price(lower_tick, decimals_a, decimals_b)?,
+            tick_to_price(upper_tick, decimals_a, decimals_b)?,
+        ))
+    }
+
+    /// Returns the fee rate a swap would pay right now, in hundredths of a basis point.
+    ///
+    /// For pools with an enabled adaptive fee this is the adaptive `current_rate`, which rises
+    /// above the static `fee_rate` while the market is volatile; it never drops below
+    /// `fee_rate`. Other pools pay `fee_rate`.
+    pub fn effective_fee_rate(&self) -> u32 {
+        match &self.adaptive_fee {
+            Some(adaptive_fee) if self.adaptive_fee_enabled => {
+                adaptive_fee.current_rate.max(self.fee_rate)
+            }
+            _ => self.fee_rate,
+        }
+    }
+
+    /// Returns `fee_rate` as a percentage of the swap amount, e.g. `0.3` for a `fee_rate` of
+    /// 3000 over `FEE_RATE_DENOMINATOR`.
+    pub fn fee_rate_percent(&self) -> Decimal {
+        rate_percent(self.fee_rate, FEE_RATE_DENOMINATOR)
+    }
+
+    /// Returns `fee_rate` in basis points of the swap amount, e.g. `30` for a `fee_rate` of
+    /// 3000.
+    pub fn fee_rate_bps(&self) -> Decimal {
+        rate_bps(self.fee_rate, FEE_RATE_DENOMINATOR)
+    }
+
+    /// Returns `protocol_fee_rate` as a percentage of the trading fee, e.g. `13` for a
+    /// `protocol_fee_rate` of 1300 over `PROTOCOL_FEE_RATE_DENOMINATOR`.
+    pub fn protocol_fee_rate_percent(&self) -> Decimal {
+        rate_percent(self.protocol_fee_rate, PROTOCOL_FEE_RATE_DENOMINATOR)
+    }
+
+    /// Returns `protocol_fee_rate` in basis points of the trading fee, which is its raw value.
+    pub fn protocol_fee_rate_bps(&self) -> Decimal {
+        rate_bps(self.protocol_fee_rate, PROTOCOL_FEE_RATE_DENOMINATOR)
+    }
+
+    /// Returns `true` if a swap through this pool currently pays a lower fee than through
+    /// `other`, comparing `effective_fee_rate`.
+    pub fn cheaper_than(&self, other: &Whirlpool) -> bool {
+        self.effective_fee_rate() < other.effective_fee_rate()
+    }
+
+    /// Compares pools by `tvl_usdc`, smallest first.
+    ///
+    /// A TVL that does not parse as a decimal orders after every parsed one.
+    pub fn cmp_by_tvl(&self, other: &Self) -> Ordering {
+        cmp_parsed_last(self.tvl_decimal(), other.tvl_decimal())
+    }
+
+    /// Compares pools by their trading volume over `period`, smallest first.
+    ///
+    /// A pool without stats for `period`, or whose volume does not parse, orders after every
+    /// pool with a volume.
+    pub fn cmp_by_volume(&self, other: &Self, period: TimePeriod) -> Ordering {
+        cmp_parsed_last(self.volume_decimal(period), other.volume_decimal(period))
+    }
+
+    /// Returns `tvl_usdc` as a decimal, or `None` if it does not parse.
+    fn tvl_decimal(&self) -> Option<Decimal> {
+        parse_decimal("tvl_usdc", &self.tvl_usdc).ok()
+    }
+
+    /// Returns the volume over `period` as a decimal, or `None` if it is missing or does not
+    /// parse.
+    fn volume_decimal(&self, period: TimePeriod) -> Option<Decimal> {
+        let stats = self.stats.get(&period)?;
+        parse_decimal("volume", &stats.volume).ok()
+    }
+
+    /// Lists the fields that changed from this snapshot of the pool to `newer`.
+    ///
+    /// Price, `sqrt_price`, current tick, liquidity, TVL and fee rate are compared, along
+    /// with the set of active reward mints. Numeric strings are compared by value.
+    pub fn diff(&self, newer: &Whirlpool) -> WhirlpoolDiff {
+        let mints = |pool: &Whirlpool| -> Vec<Pubkey> {
+            pool.active_rewards()
+                .into_iter()
+                .map(|reward| reward.mint.clone())
+                .collect()
+        };
+        let (old_mints, new_mints) = (mints(self), mints(newer));
+        WhirlpoolDiff {
+            price: numeric_change("price", &self.price, &newer.price),
+            sqrt_price: numeric_change("sqrt_price", &self.sqrt_price, &newer.sqrt_price),
+            tick_current_index: FieldChange::between(
+                self.tick_current_index,
+                newer.tick_current_index,
+            ),
+            liquidity: numeric_change("liquidity", &self.liquidity, &newer.liquidity),
+            tvl_usdc: numeric_change("tvl_usdc", &self.tvl_usdc, &newer.tvl_usdc),
+            fee_rate: FieldChange::between(self.fee_rate, newer.fee_rate),
+            rewards_added: new_mints
+                .iter()
+                .filter(|mint| !old_mints.contains(mint))
+                .cloned()
+                .collect(),
+            rewards_removed: old_mints
+                .iter()
+                .filter(|mint| !new_mints.contains(mint))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Scores the pool as a liquidity-provision opportunity; higher is better.
+    ///
+    /// The score is `(fee_apr + reward_apr) * tvl / (tvl + LP_SCORE_TVL_SCALE)`, where
+    /// `fee_apr = fees_24h * 365 / tvl` and `reward_apr = rewards_24h * 365 / tvl`. The TVL
+    /// factor tends to 1 for deep pools and to 0 for shallow ones, whose APR is easily
+    /// diluted by new liquidity. Pools without TVL or 24h stats score zero.
+    pub fn lp_opportunity_score(&self) -> Result<Decimal, OrcaError> {
+        let tvl = parse_decimal("tvl_usdc", &self.tvl_usdc)?;
+        let stats = match self.stats.get(&TimePeriod::H24) {
+            Some(stats) if tvl > Decimal::ZERO => stats,
+            _ => return Ok(Decimal::ZERO),
+        };
+        let overflow = || OrcaError::Overflow("lp_opportunity_score");
+        let fees = parse_decimal("fees", &stats.fees)?;
+        let rewards = parse_decimal("rewards", &stats.rewards)?;
+        let yearly = fees
+            .checked_add(rewards)
+            .and_then(|daily| daily.checked_mul(Decimal::from(365)))
+            .ok_or_else(overflow)?;
+        let apr = yearly.checked_div(tvl).ok_or_else(overflow)?;
+        let depth = tvl
+            .checked_add(Decimal::from(LP_SCORE_TVL_SCALE))
+            .and_then(|scaled| tvl.checked_div(scaled))
+            .ok_or_else(overflow)?;
+        apr.checked_mul(depth).ok_or_else(overflow)
+    }
+
+    /// Converts `tvl_usdc` into units of another token.
+    ///
+    /// `unit_price_usdc` is the price of one unit of the target token in USDC, e.g. `150` to
+    /// express the TVL in SOL when SOL trades at 150 USDC. The result is
+    /// `tvl_usdc / unit_price_usdc`. Fails with `OrcaError::Validation` if the price is not
+    /// positive.
+    pub fn tvl_in(&self, unit_price_usdc: Decimal) -> Result<Decimal, OrcaError> {
+        if unit_price_usdc <= Decimal::ZERO {
+            return Err(OrcaError::Validation(format!(
+                "`unit_price_usdc` must be positive, got {}",
+                unit_price_usdc
+            )));
+        }
+        let tvl = parse_decimal("tvl_usdc", &self.tvl_usdc)?;
+        tvl.checked_div(unit_price_usdc)
+            .ok_or(OrcaError::Overflow("tvl_in"))
+    }
+
+    /// Returns the value locked in the pool in units of token A, without a USDC price.
+    ///
+    /// Token B's balance is converted at the pool's own `price` (token B per token A):
+    /// `token_balance_a + token_balance_b / price`. Fails with `OrcaError::Validation` if
+    /// `price` is not positive.
+    pub fn tvl_in_token_a(&self) -> Result<Decimal, OrcaError> {
+        let (balance_a, balance_b, price) = self.balances_and_price()?;
+        balance_b
+            .checked_div(price)
+            .and_then(|b_in_a| balance_a.checked_add(b_in_a))
+            .ok_or(OrcaError::Overflow("tvl_in_token_a"))
+    }
+
+    /// Returns the value locked in the pool in units of token B, without a USDC price.
+    ///
+    /// Token A's balance is converted at the pool's own `price` (token B per token A):
+    /// `token_balance_a * price + token_balance_b`. Fails with `OrcaError::Validation` if
+    /// `price` is not positive.
+    pub fn tvl_in_token_b(&self) -> Result<Decimal, OrcaError> {
+        let (balance_a, balance_b, price) = self.balances_and_price()?;
+        balance_a
+            .checked_mul(price)
+            .and_then(|a_in_b| a_in_b.checked_add(balance_b))
+            .ok_or(OrcaError::Overflow("tvl_in_token_b"))
+    }
+
+    /// Parses the token balances and a positive `price`.
+    fn balances_and_price(&self) -> Result<(Decimal, Decimal, Decimal), OrcaError> {
+        let balance_a = parse_decimal("token_balance_a", &self.token_balance_a)?;
+        let balance_b = parse_decimal("token_balance_b", &self.token_balance_b)?;
+        let price = parse_decimal("price", &self.price)?;
+        if price <= Decimal::ZERO {
+            return Err(OrcaError::Validation(format!(
+                "`price` must be positive, got {}",
+                price
+            )));
+        }
+        Ok((balance_a, balance_b, price))
+    }
+
+    /// Annualizes the fees and rewards earned over `period` into an APR, as a fraction of TVL.
+    ///
+    /// The formula is `(fees + rewards) * periods_per_year / tvl_usdc`, where
+    /// `periods_per_year` is the number of such periods in a 365-day year (365 for `H24`,
+    /// 8760 for `H1`). It assumes the period's volume, fee rate, reward emissions and TVL all
+    /// stay constant for the rest of the year, and it does not compound, so it is an APR
+    /// rather than an APY. Returns `None` if the pool has no stats for `period`, its TVL is
+    /// zero, or a value fails to parse.
+    pub fn apr_from_period(&self, period: TimePeriod) -> Option<Decimal> {
+        let tvl = parse_decimal("tvl_usdc", &self.tvl_usdc).ok()?;
+        if tvl <= Decimal::ZERO {
+            return None;
+        }
+        let stats = self.stats.get(&period)?;
+        let fees = parse_decimal("fees", &stats.fees).ok()?;
+        let rewards = parse_decimal("rewards", &stats.rewards).ok()?;
+        let periods_per_year = Decimal::from(365 * 24 * 60 / period.minutes());
+        fees.checked_add(rewards)?
+            .checked_mul(periods_per_year)?
+            .checked_div(tvl)
+    }
+
+    /// Returns how many units of `output_mint` one unit of `input_mint` buys through this pool,
+    /// net of the pool's trading fee.
+    ///
+    /// Based on the reported spot `price` (token B per token A, in UI units), inverted when
+    /// swapping B for A, then scaled by `1 - effective_fee_rate() / 1_000_000`. Price impact
+    /// is ignored, so this only holds for trades that are small relative to the pool's
+    /// liquidity.
+    pub fn effective_rate(
+        &self,
+        input_mint: &str,
+        output_mint: &str,
+    ) -> Result<Decimal, OrcaError> {
+        let price = parse_decimal("price", &self.price)?;
+        let rate = if self.token_mint_a == input_mint && self.token_mint_b == output_mint {
+            price
+        } else if self.token_mint_b == input_mint && self.token_mint_a == output_mint {
+            if price.is_zero() {
+                return Err(OrcaError::Validation(format!(
+                    "pool {} has a zero price",
+                    self.address
+                )));
+            }
+            Decimal::ONE / price
+        } else {
+            return Err(OrcaError::Validation(format!(
+                "pool {} does not trade {} for {}",
+                self.address, input_mint, output_mint
+            )));
+        };
+        let fee = Decimal::from(self.effective_fee_rate()) / Decimal::from(FEE_RATE_DENOMINATOR);
+        Ok(rate * (Decimal::ONE - fee))
+    }
+
+    /// Returns the rewards currently emitting.
+    ///
+    /// A pool has a fixed number of reward slots. The API reports every slot, flagging the
+    /// initialized ones as `active`; uninitialized slots also carry the all-zero mint. Both
+    /// checks are applied, so only configured rewards are returned. An active reward may
+    /// still be emitting nothing if its rate was set to zero.
+    pub fn active_rewards(&self) -> Vec<&Reward> {
+        self.rewards
+            .iter()
+            .filter(|reward| reward.active && !reward.mint.is_default())
+            .collect()
+    }
+
+    /// Returns each active reward's mint with its fraction of the pool's total
+    /// `emissions_per_second`.
+    ///
+    /// Emissions are summed as raw per-second amounts, regardless of each mint's decimals or
+    /// price. If the active rewards emit nothing in total, every share is zero.
+    pub fn reward_emission_shares(&self) -> Result<Vec<(String, Decimal)>, OrcaError> {
+        let emissions = self
+            .active_rewards()
+            .into_iter()
+            .map(|reward| {
+                let amount = reward.emissions_per_second_decimal()?;
+                Ok((reward.mint.to_string(), amount))
+            })
+            .collect::<Result<Vec<_>, OrcaError>>()?;
+        let total = emissions
+            .iter()
+            .try_fold(Decimal::ZERO, |total, (_, amount)| {
+                total.checked_add(*amount)
+            })
+            .ok_or(OrcaError::Overflow("reward_emission_shares"))?;
+        Ok(emissions
+            .into_iter()
+            .map(|(mint, amount)| {
+                let share = if total.is_zero() {
+                    Decimal::ZERO
+                } else {
+                    amount / total
+                };
+                (mint, share)
+            })
+            .collect())
+    }
+
+    /// Quotes the output of swapping `amount_in` through this pool, in UI units, net of the
+    /// trading fee.
+    ///
+    /// `a_to_b` swaps token A for token B; otherwise B for A. The quote uses
+    /// `effective_rate`, i.e. the spot price without price impact.
+    pub fn quote_amount_out(&self, amount_in: Decimal, a_to_b: bool) -> Result<Decimal, OrcaError> {
+        let rate = if a_to_b {
+            self.effective_rate(&self.token_mint_a, &self.token_mint_b)?
+        } else {
+            self.effective_rate(&self.token_mint_b, &self.token_mint_a)?
+        };
+        amount_in
+            .checked_mul(rate)
+            .ok_or(OrcaError::Overflow("quote_amount_out"))
+    }
+
+    /// Returns the minimum output to accept when swapping `amount_in`, in UI units: the
+    /// `quote_amount_out` reduced by `slippage_bps` basis points.
+    ///
+    /// This is the `minimum_amount_out` of the on-chain swap instruction, after converting it
+    /// to the output token's smallest units. Fails if `slippage_bps` exceeds 10,000.
+    pub fn min_out_with_slippage(
+        &self,
+        amount_in: Decimal,
+        a_to_b: bool,
+        slippage_bps: u16,
+    ) -> Result<Decimal, OrcaError> {
+        if slippage_bps > 10_000 {
+            return Err(OrcaError::Validation(format!(
+                "slippage of {} bps exceeds 100%",
+                slippage_bps
+            )));
+        }
+        let quote = self.quote_amount_out(amount_in, a_to_b)?;
+        let kept = Decimal::from(10_000 - slippage_bps) / Decimal::from(10_000);
+        quote
+            .checked_mul(kept)
+            .ok_or(OrcaError::Overflow("min_out_with_slippage"))
+    }
+
+    /// Returns a 0..=1 volatility score from the adaptive fee's volatility accumulator.
+    ///
+    /// The score is `volatility_accumulator / max_volatility_accumulator`, clamped to `1.0`.
+    /// Returns `None` for pools without an adaptive fee, where no accumulator is tracked.
+    pub fn volatility_proxy(&self) -> Option<f64> {
+        let adaptive_fee = self
+            .adaptive_fee
+            .as_ref()
+            .filter(|_| self.adaptive_fee_enabled)?;
+        let max = adaptive_fee.constants.max_volatility_accumulator;
+        if max == 0 {
+            return None;
+        }
+        let accumulator = adaptive_fee.variables.volatility_accumulator;
+        Some((f64::from(accumulator) / f64::from(max)).min(1.0))
+    }
+
+    /// Checks the pool record for internal consistency.
+    ///
+    /// A pool is rejected if any address field is not a plausible base58 public key, both
+    /// mints are the same, the embedded token infos disagree with the mints, `tick_spacing`
+    /// is zero, `sqrt_price` or `liquidity` is not an unsigned integer, or `price` or
+    /// `tvl_usdc` is not a non-negative decimal.
+    pub fn validate(&self) -> Result<(), OrcaError> {
+        for (field, value) in [
+            ("address", &self.address),
+            ("token_mint_a", &self.token_mint_a),
+            ("token_mint_b", &self.token_mint_b),
+            ("whirlpools_config", &self.whirlpools_config),
+        ] {
+            if !is_base58_pubkey(value) {
+                return Err(OrcaError::Validation(format!(
+                    "`{field}` is not a valid public key: {value:?}"
+                )));
+            }
+        }
+        if self.token_mint_a == self.token_mint_b {
+            return Err(OrcaError::Validation(
+                "`token_mint_a` and `token_mint_b` are the same".to_string(),
+            ));
+        }
+        if self.token_a.address != self.token_mint_a || self.token_b.address != self.token_mint_b {
+            return Err(OrcaError::Validation(
+                "`token_a`/`token_b` do not match the pool mints".to_string(),
+            ));
+        }
+        if self.tick_spacing == 0 {
+            return Err(OrcaError::Validation("`tick_spacing` is zero".to_string()));
+        }
+        for (field, value) in [
+            ("sqrt_price", &self.sqrt_price),
+            ("liquidity", &self.liquidity),
+        ] {
+            if parse_u128(field, value).is_err() {
+                return Err(OrcaError::Validation(format!(
+                    "`{field}` is not an unsigned integer: {value:?}"
+                )));
+            }
+        }
+        for (field, value) in [("price", &self.price), ("tvl_usdc", &self.tvl_usdc)] {
+            if parse_decimal(field, value)?.is_sign_negative() {
+                return Err(OrcaError::Validation(format!(
+                    "`{field}` is negative: {value:?}"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns `tick_spacing_seed` decoded as the little-endian `u16` it encodes.
+    pub fn tick_spacing_seed_value(&self) -> u16 {
+        u16::from_le_bytes(self.tick_spacing_seed)
+    }
+
+    /// Derives the pool's address from its seeds and checks it against `address`.
+    ///
+    /// The whirlpool PDA is seeded with `["whirlpool", whirlpools_config, token_mint_a,
+    /// token_mint_b, fee_tier_index (u16 LE)]` and `whirlpool_bump`; for pools without an
+    /// adaptive fee tier `fee_tier_index` equals `tick_spacing`. Returns the derived address,
+    /// or `OrcaError::AddressMismatch` if it differs from the reported one.
+    #[cfg(feature = "solana-sdk")]
+    pub fn derive_expected_address(&self) -> Result<String, OrcaError> {
+        use solana_pubkey::Pubkey as SolanaPubkey;
+
+        let pubkey = |field: &'static str, value: &str| {
+            SolanaPubkey::from_str(value).map_err(|_| OrcaError::InvalidPubkey {
+                field,
+                value: value.to_string(),
+            })
+        };
+        let program = pubkey("program_id", WHIRLPOOL_PROGRAM_ID)?;
+        let config = pubkey("whirlpools_config", &self.whirlpools_config)?;
+        let mint_a = pubkey("token_mint_a", &self.token_mint_a)?;
+        let mint_b = pubkey("token_mint_b", &self.token_mint_b)?;
+        let fee_tier =
+            u16::try_from(self.fee_tier_index).map_err(|_| OrcaError::InvalidNumber {
+                field: "fee_tier_index",
+                value: self.fee_tier_index.to_string(),
+            })?;
+
+        let derived = SolanaPubkey::create_program_address(
+            &[
+                b"whirlpool",
+                config.as_ref(),
+                mint_a.as_ref(),
+                mint_b.as_ref(),
+                &fee_tier.to_le_bytes(),
+                &[self.whirlpool_bump],
+            ],
+            &program,
+        )
+        .map_err(|_| OrcaError::InvalidSeeds)?
+        .to_string();
+
+        if self.address != derived {
+            return Err(OrcaError::AddressMismatch {
+                derived,
+                reported: self.address.to_string(),
+            });
+        }
+        Ok(derived)
+    }
+}
+
+/// Returns the price of `a_mint` in units of `c_mint` when routing through `pool_ab` then
+/// `pool_bc`, with `b_mint` as the shared middle token.
+///
+/// The result is the product of each hop's `Whirlpool::effective_rate`, so both pools' fees
+/// are included. It is an approximation built from spot prices: price impact, tick crossings
+/// and transfer fees are not modelled.
+pub fn route_price(
+    pool_ab: &Whirlpool,
+    pool_bc: &Whirlpool,
+    a_mint: &str,
+    b_mint: &str,
+    c_mint: &str,
+) -> Result<Decimal, OrcaError> {
+    let first = pool_ab.effective_rate(a_mint, b_mint)?;
+    let second = pool_bc.effective_rate(b_mint, c_mint)?;
+    first
+        .checked_mul(second)
+        .ok_or(OrcaError::Overflow("route_price"))
+}
+
+impl std::fmt::Display for Whirlpool {
+    /// Formats a one-line summary, e.g. `SOL/USDC 0.04% TVL $100M vol24h $300M`. The volume
+    /// is omitted if the pool has no 24h stats.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let fee_percent = self.fee_rate_percent();
+        write!(
+            f,
+            "{}/{} {}% TVL {}",
+            self.token_a.symbol,
+            self.token_b.symbol,
+            fee_percent.normalize(),
+            UsdAmount(&self.tvl_usdc)
+        )?;
+        if let Some(stats) = self.stats.get(&TimePeriod::H24) {
+            write!(f, " vol24h {}", UsdAmount(&stats.volume))?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats a USDC amount string compactly, e.g. `$1.2M`, falling back to the raw string if
+/// it is not a number.
+struct UsdAmount<'a>(&'a str);
+
+impl std::fmt::Display for UsdAmount<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Ok(value) = parse_decimal("amount", self.0) else {
+            return f.write_str(self.0);
+        };
+        let units = [
+            (Decimal::from(1_000_000_000), "B"),
+            (Decimal::from(1_000_000), "M"),
+            (Decimal::from(1_000), "k"),
+        ];
+        for (scale, suffix) in units {
+            if value.abs() >= scale {
+                let scaled = (value / scale).round_dp(1).normalize();
+                return write!(f, "${}{}", scaled, suffix);
+            }
+        }
+        write!(f, "${}", value.round_dp(2).normalize())
+    }
+}
+
+/// Orders parsed values ascending, with `None` after all of them.
+fn cmp_parsed_last(a: Option<Decimal>, b: Option<Decimal>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// Sorts `pools` by `tvl_usdc`, deepest first.
+///
+/// Pools whose TVL does not parse as a decimal go last, keeping their relative order. Each
+/// TVL is parsed once.
+pub fn sort_pools_by_tvl(pools: &mut [Whirlpool]) {
+    pools.sort_by_cached_key(|pool| {
+        let tvl = pool.tvl_decimal();
+        (tvl.is_none(), Reverse(tvl))
+    });
+}
+
+/// Counts how many of `pools` fall into each TVL bucket.
+///
+/// `buckets` holds the lower edge of each bucket, in USDC; a pool is counted in the bucket
+/// with the highest edge not above its `tvl_usdc`. Pools below the lowest edge are not
+/// counted. The result lists each edge in ascending order with its count.
+pub fn tvl_histogram(
+    pools: &[Whirlpool],
+    buckets: &[Decimal],
+) -> Result<Vec<(Decimal, usize)>, OrcaError> {
+    let mut histogram: Vec<(Decimal, usize)> = buckets.iter().map(|&edge| (edge, 0)).collect();
+    histogram.sort_by_key(|(edge, _)| *edge);
+    histogram.dedup_by_key(|(edge, _)| *edge);
+    for pool in pools {
+        let tvl = parse_decimal("tvl_usdc", &pool.tvl_usdc)?;
+        let index = histogram.partition_point(|(edge, _)| *edge <= tvl);
+        if index > 0 {
+            histogram[index - 1].1 += 1;
+        }
+    }
+    Ok(histogram)
+}
+
+/// Returns `true` if `value` looks like a base58-encoded 32-byte public key.
+fn is_base58_pubkey(value: &str) -> bool {
+    const ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+    (32..=44).contains(&value.len()) && value.chars().all(|c| ALPHABET.contains(c))
+}
+
+/// Reassembles a public key from the integer array form the API uses for some address
+/// fields.
+///
+/// Two layouts are accepted: 32 byte values, or four `u64` words holding the key bytes in
+/// little-endian order. Anything else, including an empty array, is an invalid public key.
+fn pubkey_from_words(field: &'static str, words: &[u64]) -> Result<Pubkey, OrcaError> {
+    let mut bytes = [0u8; 32];
+    match words.len() {
+        32 if words.iter().all(|&word| word <= u64::from(u8::MAX)) => {
+            for (byte, &word) in bytes.iter_mut().zip(words) {
+                *byte = word as u8;
+            }
+        }
+        4 => {
+            for (chunk, word) in bytes.chunks_exact_mut(8).zip(words) {
+                chunk.copy_from_slice(&word.to_le_bytes());
+            }
+        }
+        _ => {
+            return Err(OrcaError::InvalidPubkey {
+                field,
+                value: format!("{:?}", words),
+            })
+        }
+    }
+    Ok(Pubkey::from_bytes(&bytes))
+}
+
+/// Parses a byte seed given as a JSON-style byte array (`"[4,0]"`) or, for a single byte, a
+/// bare number (`"255"`).
+fn parse_seed_bytes(value: &str) -> Option<Vec<u8>> {
+    let value = value.trim();
+    let list = value
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+        .unwrap_or(value);
+    if list.trim().is_empty() {
+        return Some(Vec::new());
+    }
+    list.split(',')
+        .map(|byte| byte.trim().parse().ok())
+        .collect()
+}
+
+/// The encodings the API uses for byte seeds.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SeedRepr {
+    Text(String),
+    Bytes(Vec<u8>),
+    Byte(u8),
+}
+
+/// Deserializes a fixed-size byte seed sent as a string, a JSON array or a single number.
+fn deserialize_seed<'de, D, const N: usize>(deserializer: D) -> Result<[u8; N], D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let bytes = match SeedRepr::deserialize(deserializer)? {
+        SeedRepr::Text(value) => parse_seed_bytes(&value)
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid byte seed: {value:?}")))?,
+        SeedRepr::Bytes(bytes) => bytes,
+        SeedRepr::Byte(byte) => vec![byte],
+    };
+    let len = bytes.len();
+    bytes
+        .try_into()
+        .map_err(|_| serde::de::Error::custom(format!("expected a {N}-byte seed, got {len} bytes")))
+}
+
+/// Deserializes a bump seed, a single byte in any encoding `deserialize_seed` accepts.
+fn deserialize_bump<'de, D>(deserializer: D) -> Result<u8, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let [bump] = deserialize_seed(deserializer)?;
+    Ok(bump)
 }
 
 /// Information about adaptive fees.
 #[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct AdaptiveFee {
     pub constants: AdaptiveFeeConstants,
-    #[serde(rename = "currentRate")]
+    #[serde(rename = "currentRate", deserialize_with = "string_or_int")]
     pub current_rate: u32,
-    #[serde(rename = "maxRate")]
+    #[serde(rename = "maxRate", deserialize_with = "string_or_int")]
     pub max_rate: u32,
     pub variables: AdaptiveFeeVariables,
 }
 
+impl AdaptiveFee {
+    /// Returns `current_rate` as a percentage of the swap amount. Like `Whirlpool::fee_rate`,
+    /// the raw rate is over `FEE_RATE_DENOMINATOR`.
+    pub fn current_rate_percent(&self) -> Decimal {
+        rate_percent(self.current_rate, FEE_RATE_DENOMINATOR)
+    }
+
+    /// Returns `current_rate` in basis points of the swap amount.
+    pub fn current_rate_bps(&self) -> Decimal {
+        rate_bps(self.current_rate, FEE_RATE_DENOMINATOR)
+    }
+
+    /// Returns `max_rate` as a percentage of the swap amount.
+    pub fn max_rate_percent(&self) -> Decimal {
+        rate_percent(self.max_rate, FEE_RATE_DENOMINATOR)
+    }
+
+    /// Returns `max_rate` in basis points of the swap amount.
+    pub fn max_rate_bps(&self) -> Decimal {
+        rate_bps(self.max_rate, FEE_RATE_DENOMINATOR)
+    }
+}
+
 /// Constants for adaptive fees.
 #[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct AdaptiveFeeConstants {
     #[serde(rename = "adaptiveFeeControlFactor")]
     pub adaptive_fee_control_factor: u32,
@@ -236,11 +1859,18 @@ pub struct AdaptiveFeeConstants {
 
 /// Variables for adaptive fees.
 #[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct AdaptiveFeeVariables {
-    #[serde(rename = "lastMajorSwapTimestamp")]
-    pub last_major_swap_timestamp: String,
-    #[serde(rename = "lastReferenceUpdateTimestamp")]
-    pub last_reference_update_timestamp: String,
+    #[serde(
+        rename = "lastMajorSwapTimestamp",
+        deserialize_with = "deserialize_timestamp"
+    )]
+    pub last_major_swap_timestamp: DateTime<Utc>,
+    #[serde(
+        rename = "lastReferenceUpdateTimestamp",
+        deserialize_with = "deserialize_timestamp"
+    )]
+    pub last_reference_update_timestamp: DateTime<Utc>,
     #[serde(rename = "tickGroupIndexReference")]
     pub tick_group_index_reference: i32,
     #[serde(rename = "volatilityAccumulator")]
@@ -251,37 +1881,1503 @@ pub struct AdaptiveFeeVariables {
 
 /// Information about a reward.
 #[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct Reward {
-    pub authority: String,
+    pub authority: Pubkey,
     pub emissions_per_second_x64: String,
     pub growth_global_x64: String,
-    pub mint: String,
-    pub vault: String,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
     pub active: bool,
-    #[serde(rename = "emissionsPerSecond")]
+    #[serde(rename = "emissionsPerSecond", deserialize_with = "string_or_number")]
     pub emissions_per_second: String,
 }
 
+impl Reward {
+    /// Parses `emissions_per_second`, the reward tokens emitted per second.
+    pub fn emissions_per_second_decimal(&self) -> Result<Decimal, OrcaError> {
+        parse_decimal("emissions_per_second", &self.emissions_per_second)
+    }
+
+    /// Returns the reward tokens emitted per day, `emissions_per_second * 86_400`.
+    pub fn emissions_per_day(&self) -> Result<Decimal, OrcaError> {
+        self.emissions_per_second_decimal()?
+            .checked_mul(Decimal::from(86_400))
+            .ok_or(OrcaError::Overflow("emissions_per_day"))
+    }
+
+    /// Decodes `emissions_per_second_x64`, the on-chain emission rate in the reward mint's
+    /// smallest units per second as a Q64.64 fixed-point integer, by dividing it by `2^64`.
+    pub fn decoded_emissions_per_second_x64(&self) -> Result<Decimal, OrcaError> {
+        let raw: u128 =
+            self.emissions_per_second_x64
+                .parse()
+                .map_err(|_| OrcaError::InvalidNumber {
+                    field: "emissions_per_second_x64",
+                    value: self.emissions_per_second_x64.clone(),
+                })?;
+        let integer = u64::try_from(raw >> 64)
+            .map_err(|_| OrcaError::Overflow("decoded_emissions_per_second_x64"))?;
+        let fraction = Decimal::from(raw as u64) / Decimal::from(1u128 << 64);
+        Ok(Decimal::from(integer) + fraction)
+    }
+}
+
 /// Statistics for a pool.
 #[derive(Debug, Deserialize)]
 pub struct PoolStats {
+    #[serde(deserialize_with = "string_or_number")]
     pub fees: String,
+    #[serde(deserialize_with = "string_or_number")]
     pub rewards: String,
+    #[serde(deserialize_with = "string_or_number")]
     pub volume: String,
-    #[serde(rename = "yieldOverTvl")]
+    #[serde(rename = "yieldOverTvl", deserialize_with = "string_or_number")]
     pub yield_over_tvl: String,
+    /// Per-period metrics returned by the API that this struct does not model yet, such as
+    /// `txCount`.
+    #[serde(flatten, deserialize_with = "deserialize_extra")]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// A single entry of the array form of a pool's `stats` field.
+#[derive(Deserialize)]
+struct PeriodPoolStats {
+    #[serde(alias = "timeframe")]
+    period: TimePeriod,
+    #[serde(flatten)]
+    stats: PoolStats,
+}
+
+/// The shapes the API uses for a pool's `stats` field.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PoolStatsRepr {
+    Map(HashMap<TimePeriod, PoolStats>),
+    List(Vec<PeriodPoolStats>),
+}
+
+/// Deserializes pool stats given either as an object keyed by period or as an array of
+/// entries tagged with `period`/`timeframe`.
+fn deserialize_pool_stats<'de, D>(
+    deserializer: D,
+) -> Result<HashMap<TimePeriod, PoolStats>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(match Option::<PoolStatsRepr>::deserialize(deserializer)? {
+        Some(PoolStatsRepr::Map(stats)) => stats,
+        Some(PoolStatsRepr::List(entries)) => entries
+            .into_iter()
+            .map(|entry| (entry.period, entry.stats))
+            .collect(),
+        None => HashMap::new(),
+    })
+}
+
+/// Deserializes the `extra` map of fields a struct does not model.
+///
+/// With the `strict-schema` feature, any such field is an error instead, naming the fields.
+fn deserialize_extra<'de, D>(
+    deserializer: D,
+) -> Result<HashMap<String, serde_json::Value>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let extra = HashMap::<String, serde_json::Value>::deserialize(deserializer)?;
+    #[cfg(feature = "strict-schema")]
+    if !extra.is_empty() {
+        let mut fields: Vec<&str> = extra.keys().map(String::as_str).collect();
+        fields.sort_unstable();
+        return Err(serde::de::Error::custom(format!(
+            "unknown fields: {}",
+            fields.join(", ")
+        )));
+    }
+    Ok(extra)
+}
+
+/// The encodings the API uses for decimal values.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NumberRepr {
+    Text(String),
+    Number(serde_json::Number),
+}
+
+/// Deserializes a decimal value sent either as a JSON string or as a JSON number, keeping it
+/// as a string. Numbers are written in serde_json's shortest round-trip form.
+fn string_or_number<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(match NumberRepr::deserialize(deserializer)? {
+        NumberRepr::Text(value) => value,
+        NumberRepr::Number(value) => value.to_string(),
+    })
+}
+
+/// Deserializes an integer amount sent either as a JSON string or as a JSON number, keeping
+/// it as a string.
+///
+/// Without serde_json's `arbitrary_precision`, a number outside the `i64`/`u64` range is
+/// read as an `f64` and has already lost digits, so such numbers, and non-integers, are
+/// rejected rather than stored approximately.
+fn string_or_integer<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    match NumberRepr::deserialize(deserializer)? {
+        NumberRepr::Text(value) => Ok(value),
+        NumberRepr::Number(value) if value.is_u64() || value.is_i64() => Ok(value.to_string()),
+        NumberRepr::Number(value) => Err(serde::de::Error::custom(format!(
+            "number {value} is not an integer that fits in 64 bits; send it as a string"
+        ))),
+    }
+}
+
+/// The encodings the API uses for integer values.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum IntRepr<T> {
+    Number(T),
+    Text(String),
+}
+
+/// Deserializes an integer sent either as a JSON number or as a quoted decimal string.
+fn string_or_int<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de> + FromStr,
+    T::Err: std::fmt::Display,
+{
+    match IntRepr::<T>::deserialize(deserializer)? {
+        IntRepr::Number(value) => Ok(value),
+        IntRepr::Text(value) => value
+            .trim()
+            .parse()
+            .map_err(|err| serde::de::Error::custom(format!("invalid integer {value:?}: {err}"))),
+    }
+}
+
+/// The encodings the API uses for timestamps.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TimestampRepr {
+    Integer(i64),
+    Text(String),
+}
+
+/// Epoch values at or above this are taken to be milliseconds rather than seconds; as seconds
+/// they would fall after the year 5000.
+const EPOCH_MILLIS_THRESHOLD: i64 = 100_000_000_000;
+
+/// Parses a timestamp given as RFC 3339 or as epoch seconds or milliseconds.
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    match value.trim().parse::<i64>() {
+        Ok(epoch) => from_epoch(epoch),
+        Err(_) => DateTime::parse_from_rfc3339(value.trim())
+            .ok()
+            .map(|timestamp| timestamp.with_timezone(&Utc)),
+    }
+}
+
+/// Converts epoch seconds, or milliseconds past `EPOCH_MILLIS_THRESHOLD`, to a timestamp.
+fn from_epoch(epoch: i64) -> Option<DateTime<Utc>> {
+    if epoch.abs() >= EPOCH_MILLIS_THRESHOLD {
+        DateTime::from_timestamp_millis(epoch)
+    } else {
+        DateTime::from_timestamp(epoch, 0)
+    }
+}
+
+/// Deserializes a timestamp sent as an RFC 3339 string, or as epoch seconds or milliseconds
+/// in either a string or a number.
+fn deserialize_timestamp<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let parsed = match TimestampRepr::deserialize(deserializer)? {
+        TimestampRepr::Integer(epoch) => from_epoch(epoch).ok_or_else(|| epoch.to_string()),
+        TimestampRepr::Text(value) => parse_timestamp(&value).ok_or(value),
+    };
+    parsed.map_err(|value| serde::de::Error::custom(format!("invalid timestamp {:?}", value)))
+}
+
+/// Deserializes `null` as the type's default value, for fields the API sometimes sends as
+/// `null` instead of omitting them or sending an empty value.
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de> + Default,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
 }
 
 /// Basic information about a token.
 #[derive(Debug, Deserialize)]
 pub struct SimpleTokenInfo {
-    pub address: String,
+    pub address: Pubkey,
+    #[serde(deserialize_with = "string_or_int")]
     pub decimals: u8,
     #[serde(rename = "imageUrl")]
     pub image_url: String,
     pub name: String,
     #[serde(rename = "programId")]
-    pub program_id: String,
+    pub program_id: Pubkey,
     pub symbol: String,
-    pub tags: String, // todo: parse as json
-}
\ No newline at end of file
+    /// JSON-encoded array of tags; see [`SimpleTokenInfo::parsed_tags`].
+    pub tags: String,
+    /// Fields returned by the API that this struct does not model yet.
+    #[serde(flatten, deserialize_with = "deserialize_extra")]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl SimpleTokenInfo {
+    /// Returns `true` if this token is wrapped SOL.
+    pub fn is_native_sol(&self) -> bool {
+        self.address == WSOL_MINT
+    }
+
+    /// Parses the JSON-encoded `tags` field, e.g. `"[\"verified\",\"lst\"]"`.
+    pub fn parsed_tags(&self) -> Result<Vec<String>, OrcaError> {
+        Ok(serde_json::from_str(&self.tags)?)
+    }
+
+    /// Returns `true` if the token carries the `verified` tag.
+    ///
+    /// Tags that fail to parse are treated as absent.
+    pub fn is_verified(&self) -> bool {
+        self.parsed_tags()
+            .map(|tags| tags.iter().any(|tag| tag == VERIFIED_TAG))
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::fixtures::{self, ORCA_MINT, USDC_MINT};
+    use serde_json::json;
+
+    fn whirlpool(overrides: serde_json::Value) -> Whirlpool {
+        serde_json::from_value(fixtures::merge(fixtures::whirlpool_json(), overrides)).unwrap()
+    }
+
+    #[test]
+    fn test_token_is_native_sol() {
+        let wsol: Token = serde_json::from_value(fixtures::token_json(WSOL_MINT)).unwrap();
+        let usdc: Token = serde_json::from_value(fixtures::token_json(USDC_MINT)).unwrap();
+        assert!(wsol.is_native_sol());
+        assert!(!usdc.is_native_sol());
+    }
+
+    #[test]
+    fn test_whirlpool_has_native_sol() {
+        let pool = whirlpool(json!({}));
+        assert!(pool.has_native_sol());
+        assert!(pool.token_a.is_native_sol());
+        assert!(!pool.token_b.is_native_sol());
+
+        let pool = whirlpool(json!({
+            "tokenMintA": ORCA_MINT,
+            "tokenA": { "address": ORCA_MINT, "symbol": "ORCA" }
+        }));
+        assert!(!pool.has_native_sol());
+        assert!(!pool.token_a.is_native_sol());
+        assert!(!pool.has_native_token(Chain::Solana));
+
+        let eth: Whirlpool = serde_json::from_value(fixtures::eclipse_pool_json()).unwrap();
+        assert!(eth.has_native_token(Chain::Eclipse));
+    }
+
+    #[test]
+    fn test_whirlpool_stats_object_form() {
+        let pool = whirlpool(json!({}));
+        assert_eq!(pool.stats.len(), 1);
+        assert_eq!(pool.stats[&TimePeriod::H24].fees, "120000.5");
+    }
+
+    #[test]
+    fn test_whirlpool_stats_array_form() {
+        let mut value = fixtures::whirlpool_json();
+        value["stats"] = json!([
+            {
+                "period": "24h",
+                "fees": "120000.5",
+                "rewards": "0",
+                "volume": "300001250.0",
+                "yieldOverTvl": "0.0012"
+            },
+            {
+                "timeframe": "1h",
+                "fees": "5000",
+                "rewards": "0",
+                "volume": "12500000",
+                "yieldOverTvl": "0.00005"
+            }
+        ]);
+        let pool: Whirlpool = serde_json::from_value(value).unwrap();
+        assert_eq!(pool.stats.len(), 2);
+        assert_eq!(pool.stats[&TimePeriod::H24].volume, "300001250.0");
+        assert_eq!(pool.stats[&TimePeriod::H1].fees, "5000");
+    }
+
+    #[test]
+    #[cfg(not(feature = "strict-schema"))]
+    fn test_pool_stats_extra() {
+        let pool = whirlpool(json!({ "stats": { "24h": fixtures::extended_pool_stats_json() } }));
+        let stats = &pool.stats[&TimePeriod::H24];
+        assert_eq!(stats.volume, "300001250.0");
+        assert_eq!(stats.extra.len(), 1);
+        assert_eq!(stats.extra["txCount"], json!(15230));
+
+        let mut entry = fixtures::extended_pool_stats_json();
+        entry["period"] = json!("1h");
+        let pool = whirlpool(json!({ "stats": [entry] }));
+        assert_eq!(pool.stats[&TimePeriod::H1].extra.len(), 1);
+        assert!(whirlpool(json!({})).stats[&TimePeriod::H24]
+            .extra
+            .is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "strict-schema")]
+    fn test_pool_stats_extra_rejected() {
+        let value = fixtures::merge(
+            fixtures::whirlpool_json(),
+            json!({ "stats": { "24h": fixtures::extended_pool_stats_json() } }),
+        );
+        assert!(serde_json::from_value::<Whirlpool>(value).is_err());
+    }
+
+    #[test]
+    fn test_net_yield_over_tvl() {
+        let pool = whirlpool(json!({ "yieldOverTvl": "0.002", "protocolFeeRate": 1300 }));
+        assert_eq!(
+            pool.net_yield_over_tvl().unwrap(),
+            Decimal::from_str("0.00174").unwrap()
+        );
+
+        let pool = whirlpool(json!({ "yieldOverTvl": "not-a-number" }));
+        assert!(matches!(
+            pool.net_yield_over_tvl(),
+            Err(OrcaError::InvalidNumber {
+                field: "yield_over_tvl",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_paginated_iteration_and_meta() {
+        let page: Paginated<Token> = serde_json::from_value(json!({
+            "data": [fixtures::token_json(WSOL_MINT), fixtures::token_json(USDC_MINT)],
+            "meta": { "next": "cursor-2", "previous": null }
+        }))
+        .unwrap();
+
+        assert_eq!(page.len(), 2);
+        assert_eq!(page[1].address, USDC_MINT);
+        assert!(page.meta.has_next());
+        assert!(!page.meta.has_previous());
+        assert_eq!(page.next_cursor(), Some(&Cursor::from("cursor-2")));
+
+        let stored = serde_json::to_string(page.next_cursor().unwrap()).unwrap();
+        assert_eq!(stored, r#""cursor-2""#);
+        let restored: Cursor = serde_json::from_str(&stored).unwrap();
+        assert_eq!(restored.into_string(), "cursor-2");
+
+        let borrowed: Vec<&str> = (&page).into_iter().map(|t| t.address.as_str()).collect();
+        assert_eq!(borrowed, vec![WSOL_MINT, USDC_MINT]);
+        assert_eq!(page.as_ref().len(), 2);
+
+        let owned: Vec<Token> = page.into_iter().collect();
+        assert_eq!(owned.len(), 2);
+    }
+
+    #[test]
+    fn test_meta_counts() {
+        let meta: Meta = serde_json::from_value(json!({
+            "next": "cursor-2",
+            "previous": null,
+            "total": 1200,
+            "count": 50,
+            "limit": 50
+        }))
+        .unwrap();
+        assert_eq!(meta.total, Some(1200));
+        assert_eq!(meta.count, Some(50));
+        assert_eq!(meta.limit, Some(50));
+
+        let meta: Meta = serde_json::from_value(json!({ "next": null, "previous": null })).unwrap();
+        assert_eq!(meta.total, None);
+        assert_eq!(meta.count, None);
+        assert_eq!(meta.limit, None);
+    }
+
+    #[cfg(feature = "solana-sdk")]
+    #[test]
+    fn test_derive_expected_address() {
+        let pool = whirlpool(json!({}));
+        assert_eq!(
+            pool.derive_expected_address().unwrap(),
+            fixtures::SOL_USDC_POOL
+        );
+
+        let spoofed =
+            whirlpool(json!({ "address": "HJPjoWUrhoZzkNfRpHuieeFk9WcZWjwy6PBjZ81ngndJ" }));
+        assert!(matches!(
+            spoofed.derive_expected_address(),
+            Err(OrcaError::AddressMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_whirlpool_validate() {
+        assert!(whirlpool(json!({})).validate().is_ok());
+
+        let invalid = [
+            json!({ "tokenMintB": "So11111111111111111111111111111111111111112" }),
+            json!({ "address": "not a pubkey" }),
+            json!({ "tickSpacing": 0 }),
+            json!({ "sqrtPrice": "-1" }),
+            json!({ "tvlUsdc": "-5" }),
+        ];
+        for overrides in invalid {
+            // With `validate-pubkeys`, malformed addresses are rejected while deserializing.
+            let pool: Result<Whirlpool, _> = serde_json::from_value(fixtures::merge(
+                fixtures::whirlpool_json(),
+                overrides.clone(),
+            ));
+            assert!(
+                pool.map_or(true, |pool| pool.validate().is_err()),
+                "expected {overrides} to be invalid"
+            );
+        }
+    }
+
+    #[test]
+    fn test_computed_price() {
+        let pool = whirlpool(json!({}));
+        assert_eq!(pool.sqrt_price_u128().unwrap(), 6786903707898418446);
+
+        let computed = pool
+            .computed_price(pool.token_a.decimals, pool.token_b.decimals)
+            .unwrap();
+        let reported = Decimal::from_str(&pool.price).unwrap();
+        assert!((computed - reported).abs() < Decimal::from_str("0.01").unwrap());
+
+        // sqrt_price of exactly 2^64 is a raw price of 1.
+        let pool = whirlpool(json!({ "sqrtPrice": "18446744073709551616" }));
+        assert_eq!(pool.computed_price(6, 6).unwrap(), Decimal::ONE);
+        assert_eq!(
+            pool.computed_price(6, 9).unwrap(),
+            Decimal::from_str("0.001").unwrap()
+        );
+
+        let pool = whirlpool(json!({ "sqrtPrice": "abc" }));
+        assert!(pool.computed_price(9, 6).is_err());
+    }
+
+    #[test]
+    fn test_current_tick_price_range() {
+        let pool = whirlpool(json!({}));
+        let (lower, upper) = pool.current_tick_price_range().unwrap();
+        let price = Decimal::from_str(&pool.price).unwrap();
+        assert!(lower < upper);
+        assert!(
+            lower <= price && price <= upper,
+            "{lower} <= {price} <= {upper}"
+        );
+
+        // A negative tick that is already aligned starts its own range.
+        let pool = whirlpool(json!({ "tickCurrentIndex": -20000 }));
+        let (aligned_lower, _) = pool.current_tick_price_range().unwrap();
+        assert_eq!(aligned_lower, lower);
+    }
+
+    #[test]
+    fn test_parse_decimal_rejects_non_finite() {
+        for value in [
+            "NaN",
+            "nan",
+            "+inf",
+            "inf",
+            "Infinity",
+            "-Infinity",
+            "",
+            "12abc",
+        ] {
+            assert!(
+                matches!(
+                    parse_decimal("price", value),
+                    Err(OrcaError::InvalidNumber { field: "price", .. })
+                ),
+                "{value:?} should be rejected"
+            );
+        }
+        assert_eq!(
+            parse_decimal("price", "135.3657").unwrap(),
+            Decimal::from_str("135.3657").unwrap()
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "strict-numeric"))]
+    fn test_parse_decimal_rounds_excess_precision() {
+        assert_eq!(
+            parse_decimal("price", "0.12345678901234567890123456789").unwrap(),
+            Decimal::from_str("0.1234567890123456789012345679").unwrap()
+        );
+        assert_eq!(
+            parse_decimal("price", "1.2345678901234567890123456789e-1").unwrap(),
+            Decimal::from_str("0.1234567890123456789012345679").unwrap()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "strict-numeric")]
+    fn test_parse_decimal_rejects_excess_precision() {
+        for value in [
+            "0.12345678901234567890123456789",
+            "1.2345678901234567890123456789e-1",
+        ] {
+            assert!(matches!(
+                parse_decimal("price", value),
+                Err(OrcaError::InvalidNumber { field: "price", .. })
+            ));
+        }
+        assert_eq!(
+            parse_decimal("price", "0.1234567890123456789012345678").unwrap(),
+            Decimal::from_str("0.1234567890123456789012345678").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_volatility_proxy() {
+        let adaptive = whirlpool(json!({
+            "adaptiveFeeEnabled": true,
+            "adaptiveFee": fixtures::adaptive_fee_json()
+        }));
+        assert_eq!(adaptive.volatility_proxy(), Some(0.5));
+
+        let saturated = whirlpool(json!({
+            "adaptiveFeeEnabled": true,
+            "adaptiveFee": fixtures::merge(
+                fixtures::adaptive_fee_json(),
+                json!({ "variables": { "volatilityAccumulator": 400000 } })
+            )
+        }));
+        assert_eq!(saturated.volatility_proxy(), Some(1.0));
+
+        assert_eq!(whirlpool(json!({})).volatility_proxy(), None);
+    }
+
+    #[test]
+    fn test_reward_emission_shares() {
+        let pool = whirlpool(json!({
+            "rewards": [
+                fixtures::reward_json(ORCA_MINT, "3"),
+                fixtures::reward_json(WSOL_MINT, "1"),
+                fixtures::merge(
+                    fixtures::reward_json(USDC_MINT, "100"),
+                    json!({ "active": false })
+                ),
+            ]
+        }));
+        let shares = pool.reward_emission_shares().unwrap();
+        assert_eq!(
+            shares,
+            vec![
+                (ORCA_MINT.to_string(), Decimal::from_str("0.75").unwrap()),
+                (WSOL_MINT.to_string(), Decimal::from_str("0.25").unwrap()),
+            ]
+        );
+        let total: Decimal = shares.iter().map(|(_, share)| share).sum();
+        assert_eq!(total, Decimal::ONE);
+
+        let idle = whirlpool(json!({ "rewards": [fixtures::reward_json(ORCA_MINT, "0")] }));
+        assert_eq!(
+            idle.reward_emission_shares().unwrap(),
+            vec![(ORCA_MINT.to_string(), Decimal::ZERO)]
+        );
+        assert!(whirlpool(json!({}))
+            .reward_emission_shares()
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    #[cfg(not(feature = "strict-schema"))]
+    fn test_unknown_fields_are_kept() {
+        let pool = whirlpool(json!({ "newMetric": { "value": 7 } }));
+        assert_eq!(pool.extra["newMetric"], json!({ "value": 7 }));
+        assert!(!pool.extra.contains_key("address"));
+        assert!(pool.token_a.extra.is_empty());
+
+        let token: Token = serde_json::from_value(fixtures::merge(
+            fixtures::token_json(USDC_MINT),
+            json!({ "isVerified": true }),
+        ))
+        .unwrap();
+        assert_eq!(token.extra.len(), 1);
+        assert_eq!(token.extra["isVerified"], json!(true));
+    }
+
+    #[test]
+    fn test_strict_schema() {
+        let pool = serde_json::from_value::<Whirlpool>(fixtures::merge(
+            fixtures::whirlpool_json(),
+            json!({ "newMetric": 7, "tokenA": { "isVerified": true } }),
+        ));
+        let stats = serde_json::from_value::<PoolStats>(json!({
+            "fees": "1",
+            "rewards": "0",
+            "volume": "10",
+            "yieldOverTvl": "0.1",
+            "apr": "0.2"
+        }));
+        if cfg!(feature = "strict-schema") {
+            let err = pool.unwrap_err().to_string();
+            assert!(err.contains("unknown fields"), "{}", err);
+            assert!(stats.is_err());
+        } else {
+            assert!(pool.is_ok());
+            assert!(stats.is_ok());
+        }
+        assert!(serde_json::from_value::<Whirlpool>(fixtures::whirlpool_json()).is_ok());
+    }
+
+    #[test]
+    fn test_apr_from_period() {
+        let pool = whirlpool(json!({
+            "tvlUsdc": "1000000",
+            "stats": {
+                "24h": { "fees": "1000", "rewards": "370", "volume": "0", "yieldOverTvl": "0" },
+                "1h": { "fees": "100", "rewards": "50", "volume": "0", "yieldOverTvl": "0" }
+            }
+        }));
+        // (1000 + 370) * 365 / 1_000_000
+        assert_eq!(
+            pool.apr_from_period(TimePeriod::H24),
+            Some(Decimal::from_str("0.50005").unwrap())
+        );
+        // (100 + 50) * 8760 / 1_000_000
+        assert_eq!(
+            pool.apr_from_period(TimePeriod::H1),
+            Some(Decimal::from_str("1.314").unwrap())
+        );
+        assert_eq!(pool.apr_from_period(TimePeriod::M5), None);
+
+        let empty = whirlpool(json!({ "tvlUsdc": "0" }));
+        assert_eq!(empty.apr_from_period(TimePeriod::H24), None);
+    }
+
+    #[test]
+    fn test_lock_info_percentage_decimal() {
+        let lock: LockInfo =
+            serde_json::from_value(json!({ "lockedPercentage": "0.7", "name": "Whirlpool-Lock" }))
+                .unwrap();
+        assert_eq!(
+            lock.percentage_decimal().unwrap(),
+            Decimal::from_str("0.7").unwrap()
+        );
+
+        let lock: LockInfo =
+            serde_json::from_value(json!({ "lockedPercentage": "n/a", "name": "Whirlpool-Lock" }))
+                .unwrap();
+        assert!(matches!(
+            lock.percentage_decimal(),
+            Err(OrcaError::InvalidNumber {
+                field: "locked_percentage",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_token_vault_a_pubkey() {
+        let vault = "2WLWEuKDgkDUccTpbwYp1GToYktiSB1cXvreHUwiSUVP";
+        let bytes = json!([
+            22, 95, 149, 114, 197, 169, 106, 165, 2, 221, 81, 16, 84, 162, 106, 109, 37, 142, 247,
+            193, 214, 214, 193, 174, 225, 41, 25, 20, 220, 81, 2, 50
+        ]);
+        let pool = whirlpool(json!({ "tokenVaultA": bytes, "addressLookupTable": bytes }));
+        assert_eq!(pool.token_vault_a_pubkey().unwrap(), vault);
+        assert_eq!(pool.address_lookup_table_pubkey().unwrap(), vault);
+
+        let words = json!([
+            11919526029285482262u64,
+            7884292579600227586u64,
+            12592582250970189349u64,
+            3603532657521666529u64
+        ]);
+        let pool = whirlpool(json!({ "tokenVaultA": words }));
+        assert_eq!(pool.token_vault_a_pubkey().unwrap(), vault);
+
+        assert!(matches!(
+            whirlpool(json!({})).token_vault_a_pubkey(),
+            Err(OrcaError::InvalidPubkey {
+                field: "token_vault_a",
+                ..
+            })
+        ));
+        let pool = whirlpool(json!({ "tokenVaultA": vec![256; 32] }));
+        assert!(pool.token_vault_a_pubkey().is_err());
+    }
+
+    #[test]
+    fn test_token_supply_circulating_fraction() {
+        let supply = TokenSupply {
+            circulating: Decimal::from(25),
+            total: Decimal::from(100),
+        };
+        assert_eq!(supply.circulating_fraction(), Some(Decimal::new(25, 2)));
+
+        let empty = TokenSupply {
+            circulating: Decimal::ZERO,
+            total: Decimal::ZERO,
+        };
+        assert_eq!(empty.circulating_fraction(), None);
+    }
+
+    #[test]
+    fn test_string_or_number() {
+        let quoted = whirlpool(json!({}));
+        let unquoted = whirlpool(json!({
+            "price": 135.3657,
+            "tvlUsdc": 100000000,
+            "stats": { "24h": { "fees": 120000.5, "volume": 300001250 } }
+        }));
+        assert_eq!(quoted.price, "135.3657");
+        assert_eq!(unquoted.price, "135.3657");
+        assert_eq!(unquoted.tvl_usdc, "100000000");
+        let stats = &unquoted.stats[&TimePeriod::H24];
+        assert_eq!(stats.fees, "120000.5");
+        assert_eq!(stats.volume, "300001250");
+        assert_eq!(
+            parse_decimal("price", &unquoted.price).unwrap(),
+            parse_decimal("price", &quoted.price).unwrap()
+        );
+
+        let token: Token = serde_json::from_value(fixtures::merge(
+            fixtures::token_json(USDC_MINT),
+            json!({ "priceUsdc": 1.0001, "supply": 1000000000 }),
+        ))
+        .unwrap();
+        assert_eq!(token.price_usdc, "1.0001");
+        assert_eq!(token.supply, "1000000000");
+
+        let invalid = serde_json::from_value::<Whirlpool>(fixtures::merge(
+            fixtures::whirlpool_json(),
+            json!({ "price": true }),
+        ));
+        assert!(invalid.is_err());
+    }
+
+    #[test]
+    fn test_token_to_simple() {
+        let token: Token = serde_json::from_value(fixtures::merge(
+            fixtures::token_json(ORCA_MINT),
+            json!({
+                "decimals": 6,
+                "metadata": r#"{"name":"Orca","symbol":"ORCA","image":"https://example.com/orca.png"}"#,
+                "tags": r#"["verified"]"#
+            }),
+        ))
+        .unwrap();
+        let simple = token.to_simple();
+        assert_eq!(simple.address, ORCA_MINT);
+        assert_eq!(simple.decimals, 6);
+        assert_eq!(simple.name, "Orca");
+        assert_eq!(simple.symbol, "ORCA");
+        assert_eq!(simple.image_url, "https://example.com/orca.png");
+        assert_eq!(simple.program_id, token.token_program);
+        assert!(simple.is_verified());
+
+        let bare: Token = serde_json::from_value(fixtures::token_json(USDC_MINT)).unwrap();
+        let simple = SimpleTokenInfo::from(&bare);
+        assert!(simple.name.is_empty() && simple.symbol.is_empty());
+        assert!(simple.image_url.is_empty());
+    }
+
+    #[test]
+    fn test_scientific_notation() {
+        let pool: Whirlpool =
+            serde_json::from_value(fixtures::scientific_whirlpool_json()).unwrap();
+        assert_eq!(
+            pool.liquidity_u128().unwrap(),
+            1_230_000_000_000_000_000_000
+        );
+        assert_eq!(pool.sqrt_price_u128().unwrap(), 6786903707898418446);
+        assert_eq!(
+            pool.computed_price(9, 6).unwrap(),
+            whirlpool(json!({})).computed_price(9, 6).unwrap()
+        );
+        assert!(pool.validate().is_ok());
+
+        assert_eq!(
+            parse_decimal("amount", "1.23e+21").unwrap(),
+            Decimal::from_str("1230000000000000000000").unwrap()
+        );
+        assert_eq!(
+            parse_decimal("amount", "-2.5E-7").unwrap(),
+            Decimal::from_str("-0.00000025").unwrap()
+        );
+        assert_eq!(parse_u128("amount", "4200e-2").unwrap(), 42);
+        for value in ["1.5e0", "-1e3", "1e", "e5", "1.2.3e4", "1e99999"] {
+            assert!(parse_u128("amount", value).is_err(), "{}", value);
+        }
+        assert!(parse_decimal("amount", "1e5000").is_err());
+    }
+
+    #[test]
+    fn test_scientific_notation_in_helpers() {
+        let scientific = whirlpool(json!({
+            "tvlUsdc": "1.5e8",
+            "stats": { "24h": {
+                "fees": "0", "rewards": "0", "volume": "5e8", "yieldOverTvl": "0"
+            } }
+        }));
+        let plain = whirlpool(json!({ "tvlUsdc": "100000000" }));
+        assert_eq!(scientific.cmp_by_tvl(&plain), Ordering::Greater);
+        assert_eq!(
+            scientific.cmp_by_volume(&plain, TimePeriod::H24),
+            Ordering::Greater
+        );
+        assert_eq!(
+            scientific.to_string(),
+            "SOL/USDC 0.04% TVL $150M vol24h $500M"
+        );
+    }
+
+    #[test]
+    fn test_parse_u128_rejects_rounded_floats() {
+        let rounded = "7.922816251426434e+28";
+        assert!(matches!(
+            parse_u128("sqrt_price", rounded),
+            Err(OrcaError::InvalidNumber {
+                field: "sqrt_price",
+                ..
+            })
+        ));
+        let pool = whirlpool(json!({ "sqrtPrice": rounded }));
+        assert!(pool.sqrt_price_u128().is_err());
+        assert!(pool.validate().is_err());
+
+        assert_eq!(
+            parse_u128("sqrt_price", "7.9228162514264337593543950337e28").unwrap(),
+            79228162514264337593543950337
+        );
+        assert_eq!(
+            parse_u128("liquidity", "1.23e+21").unwrap(),
+            1_230_000_000_000_000_000_000
+        );
+        assert_eq!(
+            parse_u128("liquidity", "4.54123493019430e13").unwrap(),
+            45412349301943
+        );
+    }
+
+    #[test]
+    fn test_epoch_and_slot() {
+        let token: Token = serde_json::from_value(fixtures::token_json(USDC_MINT)).unwrap();
+        let epoch = token.epoch();
+        assert_eq!(u64::from(epoch), token.updated_epoch);
+        assert!(!token.is_stale(epoch));
+        assert!(!token.is_stale(Epoch(epoch.0 - 1)));
+        assert!(token.is_stale(Epoch::from(epoch.0 + 1)));
+
+        let pool = whirlpool(json!({}));
+        assert_eq!(pool.slot(), Slot(338769284));
+        assert_eq!(pool.slot().to_string(), "338769284");
+        assert_eq!(Epoch(784).to_string(), "784");
+        assert_eq!(serde_json::to_value(pool.slot()).unwrap(), json!(338769284));
+    }
+
+    #[test]
+    fn test_string_or_integer_rejects_lossy_numbers() {
+        let sqrt_price = "\"6786903707898418446\"";
+        let text = fixtures::whirlpool_json().to_string();
+        assert!(text.contains(sqrt_price));
+        let huge = text.replace(sqrt_price, "79228162514264337593543950337");
+        let err = serde_json::from_str::<Whirlpool>(&huge).unwrap_err();
+        assert!(err.to_string().contains("send it as a string"), "{}", err);
+
+        let fractional = text.replace(sqrt_price, "6786903707898418446.5");
+        assert!(serde_json::from_str::<Whirlpool>(&fractional).is_err());
+
+        let exact = text.replace(sqrt_price, "6786903707898418446");
+        let pool: Whirlpool = serde_json::from_str(&exact).unwrap();
+        assert_eq!(pool.sqrt_price_u128().unwrap(), 6786903707898418446);
+    }
+
+    #[test]
+    fn test_string_or_int() {
+        let canonical = whirlpool(json!({}));
+        let drifted: Whirlpool =
+            serde_json::from_value(fixtures::drifted_whirlpool_json()).unwrap();
+        assert_eq!(drifted.updated_slot, canonical.updated_slot);
+        assert_eq!(drifted.updated_slot, 338769284);
+        assert_eq!(drifted.liquidity, canonical.liquidity);
+        assert_eq!(drifted.fee_rate, 400);
+        assert_eq!(drifted.tick_current_index, -19999);
+        assert_eq!(drifted.token_a.decimals, 9);
+
+        let token: Token = serde_json::from_value(fixtures::merge(
+            fixtures::token_json(USDC_MINT),
+            json!({ "decimals": "6", "updatedEpoch": " 784 " }),
+        ))
+        .unwrap();
+        assert_eq!(token.decimals, 6);
+        assert_eq!(token.updated_epoch, 784);
+
+        for value in [json!("-1"), json!("1.5"), json!("slot"), json!(true)] {
+            let invalid = serde_json::from_value::<Whirlpool>(fixtures::merge(
+                fixtures::whirlpool_json(),
+                json!({ "updatedSlot": value }),
+            ));
+            assert!(invalid.is_err(), "{value} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_tvl_in_other_units() {
+        let pool = whirlpool(json!({
+            "tvlUsdc": "3000",
+            "price": "150",
+            "tokenBalanceA": "10",
+            "tokenBalanceB": "1500"
+        }));
+        assert_eq!(pool.tvl_in(Decimal::from(150)).unwrap(), Decimal::from(20));
+        assert!(matches!(
+            pool.tvl_in(Decimal::ZERO),
+            Err(OrcaError::Validation(_))
+        ));
+        assert_eq!(pool.tvl_in_token_a().unwrap(), Decimal::from(20));
+        assert_eq!(pool.tvl_in_token_b().unwrap(), Decimal::from(3000));
+
+        let unpriced = whirlpool(json!({ "price": "0" }));
+        assert!(matches!(
+            unpriced.tvl_in_token_a(),
+            Err(OrcaError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn test_typed_seeds() {
+        let pool = whirlpool(json!({}));
+        assert_eq!(pool.whirlpool_bump, 255);
+        assert_eq!(pool.tick_spacing_seed, [4, 0]);
+        assert_eq!(pool.tick_spacing_seed_value(), 4);
+
+        for bump in [json!(254), json!("254"), json!([254]), json!(" [254] ")] {
+            let pool = whirlpool(json!({ "whirlpoolBump": bump, "tickSpacingSeed": [0, 1] }));
+            assert_eq!(pool.whirlpool_bump, 254);
+            assert_eq!(pool.tick_spacing_seed_value(), 256);
+        }
+
+        for (field, value) in [
+            ("whirlpoolBump", json!("[256]")),
+            ("whirlpoolBump", json!("[]")),
+            ("tickSpacingSeed", json!("[4,0,0]")),
+            ("tickSpacingSeed", json!("4, x")),
+        ] {
+            let invalid = serde_json::from_value::<Whirlpool>(fixtures::merge(
+                fixtures::whirlpool_json(),
+                json!({ field: value }),
+            ));
+            assert!(invalid.is_err(), "{field} = {value} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_fee_rates_in_human_units() {
+        let pool = whirlpool(json!({
+            "feeRate": 3000,
+            "protocolFeeRate": 1300,
+            "adaptiveFee": fixtures::adaptive_fee_json()
+        }));
+        assert_eq!(pool.fee_rate_percent(), Decimal::from_str("0.3").unwrap());
+        assert_eq!(pool.fee_rate_bps(), Decimal::from(30));
+        assert_eq!(pool.protocol_fee_rate_percent(), Decimal::from(13));
+        assert_eq!(pool.protocol_fee_rate_bps(), Decimal::from(1300));
+
+        let adaptive_fee = pool.adaptive_fee.as_ref().unwrap();
+        assert_eq!(
+            adaptive_fee.current_rate_percent(),
+            Decimal::from_str("0.5").unwrap()
+        );
+        assert_eq!(adaptive_fee.current_rate_bps(), Decimal::from(50));
+        assert_eq!(adaptive_fee.max_rate_percent(), Decimal::from(10));
+        assert_eq!(adaptive_fee.max_rate_bps(), Decimal::from(1000));
+
+        let tiny = whirlpool(json!({ "feeRate": 1 }));
+        assert_eq!(tiny.fee_rate_bps(), Decimal::from_str("0.01").unwrap());
+    }
+
+    #[test]
+    fn test_cheaper_than() {
+        let static_pool = whirlpool(json!({ "feeRate": 3000 }));
+        let volatile = whirlpool(json!({
+            "feeRate": 1000,
+            "adaptiveFeeEnabled": true,
+            "adaptiveFee": fixtures::adaptive_fee_json()
+        }));
+        assert_eq!(static_pool.effective_fee_rate(), 3000);
+        assert_eq!(volatile.effective_fee_rate(), 5000);
+        assert!(static_pool.cheaper_than(&volatile));
+        assert!(!volatile.cheaper_than(&static_pool));
+
+        let calm = whirlpool(json!({
+            "feeRate": 1000,
+            "adaptiveFeeEnabled": true,
+            "adaptiveFee": fixtures::merge(
+                fixtures::adaptive_fee_json(),
+                json!({ "currentRate": 1000 })
+            )
+        }));
+        assert!(calm.cheaper_than(&static_pool));
+        assert!(!static_pool.cheaper_than(&static_pool));
+    }
+
+    #[test]
+    fn test_minimal_splash_pool() {
+        let pool: Whirlpool = serde_json::from_value(fixtures::splash_pool_json()).unwrap();
+        assert_eq!(pool.pool_type, PoolType::Splash);
+        assert!(pool.rewards.is_empty());
+        assert!(pool.stats.is_empty());
+        assert!(pool.token_vault_a.is_empty());
+        assert!(pool.address_lookup_table.is_empty());
+        assert!(pool.adaptive_fee.is_none());
+        assert!(!pool.adaptive_fee_enabled);
+        assert!(!pool.has_warning);
+        assert!(pool.locked_liquidity_percent.is_none());
+    }
+
+    #[test]
+    fn test_time_period_from_str() {
+        for period in TimePeriod::ALL {
+            assert_eq!(period.to_string().parse::<TimePeriod>().unwrap(), period);
+            assert_eq!(
+                serde_json::to_value(period).unwrap(),
+                json!(period.to_string())
+            );
+        }
+        assert_eq!("1h".parse::<TimePeriod>().unwrap(), TimePeriod::H1);
+
+        let err = "1d".parse::<TimePeriod>().unwrap_err();
+        assert!(matches!(&err, OrcaError::Validation(message) if message.contains("\"1d\"")));
+    }
+
+    #[test]
+    fn test_time_period_as_query_value() {
+        let expected = [
+            (TimePeriod::M5, "5m"),
+            (TimePeriod::M15, "15m"),
+            (TimePeriod::M30, "30m"),
+            (TimePeriod::H1, "1h"),
+            (TimePeriod::H2, "2h"),
+            (TimePeriod::H4, "4h"),
+            (TimePeriod::H8, "8h"),
+            (TimePeriod::H12, "12h"),
+            (TimePeriod::H24, "24h"),
+        ];
+        for (period, value) in expected {
+            assert_eq!(period.as_query_value(), value);
+        }
+    }
+
+    #[test]
+    fn test_deserialize_timestamp() {
+        let expected = DateTime::parse_from_rfc3339("2025-05-09T00:04:50Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        for value in [
+            json!("2025-05-09T00:04:50Z"),
+            json!("2025-05-09T02:04:50+02:00"),
+            json!("1746749090"),
+            json!(1746749090),
+            json!("1746749090000"),
+            json!(1746749090000i64),
+        ] {
+            let token: Token = serde_json::from_value(fixtures::merge(
+                fixtures::token_json(USDC_MINT),
+                json!({ "updatedAt": value.clone() }),
+            ))
+            .unwrap();
+            assert_eq!(token.updated_at, expected, "parsing {value}");
+        }
+
+        let invalid = serde_json::from_value::<Token>(fixtures::merge(
+            fixtures::token_json(USDC_MINT),
+            json!({ "updatedAt": "yesterday" }),
+        ));
+        assert!(invalid.is_err());
+
+        let pool = whirlpool(json!({}));
+        assert_eq!(pool.trade_enable_timestamp, DateTime::UNIX_EPOCH);
+    }
+
+    #[test]
+    fn test_whirlpool_diff() {
+        let before = whirlpool(json!({}));
+        assert!(before.diff(&whirlpool(json!({}))).is_empty());
+        assert!(before
+            .diff(&whirlpool(json!({ "tvlUsdc": "100000000" })))
+            .is_empty());
+
+        let after = whirlpool(json!({
+            "price": "140.1",
+            "liquidity": "45500000000000",
+            "rewards": [fixtures::reward_json(ORCA_MINT, "1")]
+        }));
+        let diff = before.diff(&after);
+        assert_eq!(
+            diff.price,
+            Some(FieldChange {
+                old: "135.3657".to_string(),
+                new: "140.1".to_string()
+            })
+        );
+        assert_eq!(
+            diff.liquidity,
+            Some(FieldChange {
+                old: "45412349301943".to_string(),
+                new: "45500000000000".to_string()
+            })
+        );
+        assert_eq!(diff.tvl_usdc, None);
+        assert_eq!(diff.tick_current_index, None);
+        assert_eq!(diff.rewards_added, vec![ORCA_MINT]);
+        assert!(diff.rewards_removed.is_empty());
+        assert_eq!(after.diff(&before).rewards_removed, vec![ORCA_MINT]);
+    }
+
+    #[test]
+    fn test_pool_comparators() {
+        let small = whirlpool(json!({ "tvlUsdc": "999.5" }));
+        let large = whirlpool(json!({ "tvlUsdc": 1000 }));
+        let broken = whirlpool(json!({ "tvlUsdc": "n/a" }));
+        assert_eq!(small.cmp_by_tvl(&large), Ordering::Less);
+        assert_eq!(large.cmp_by_tvl(&small), Ordering::Greater);
+        assert_eq!(large.cmp_by_tvl(&broken), Ordering::Less);
+        assert_eq!(broken.cmp_by_tvl(&small), Ordering::Greater);
+        assert_eq!(broken.cmp_by_tvl(&broken), Ordering::Equal);
+
+        let stats = |volume: &str| {
+            json!({ "stats": { "24h": {
+                "fees": "0", "rewards": "0", "volume": volume, "yieldOverTvl": "0"
+            } } })
+        };
+        let quiet = whirlpool(stats("10"));
+        let busy = whirlpool(stats("2500.25"));
+        let unknown = whirlpool(json!({ "stats": {} }));
+        assert_eq!(quiet.cmp_by_volume(&busy, TimePeriod::H24), Ordering::Less);
+        assert_eq!(
+            busy.cmp_by_volume(&unknown, TimePeriod::H24),
+            Ordering::Less
+        );
+        assert_eq!(quiet.cmp_by_volume(&busy, TimePeriod::H1), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_sort_pools_by_tvl() {
+        let mut pools: Vec<_> = ["5", "n/a", "1000", "", "20.5"]
+            .into_iter()
+            .map(|tvl| whirlpool(json!({ "tvlUsdc": tvl })))
+            .collect();
+        sort_pools_by_tvl(&mut pools);
+        let order: Vec<&str> = pools.iter().map(|pool| pool.tvl_usdc.as_str()).collect();
+        assert_eq!(order, vec!["1000", "20.5", "5", "n/a", ""]);
+    }
+
+    #[test]
+    fn test_tvl_histogram() {
+        let pools: Vec<_> = ["5", "10", "999.99", "1000", "250000", "2000000"]
+            .into_iter()
+            .map(|tvl| whirlpool(json!({ "tvlUsdc": tvl })))
+            .collect();
+        let buckets = [
+            Decimal::from(1_000_000),
+            Decimal::from(10),
+            Decimal::from(1_000),
+        ];
+        assert_eq!(
+            tvl_histogram(&pools, &buckets).unwrap(),
+            vec![
+                (Decimal::from(10), 2),
+                (Decimal::from(1_000), 2),
+                (Decimal::from(1_000_000), 1),
+            ]
+        );
+        assert!(tvl_histogram(&pools, &[]).unwrap().is_empty());
+
+        let broken = [whirlpool(json!({ "tvlUsdc": "n/a" }))];
+        assert!(tvl_histogram(&broken, &buckets).is_err());
+    }
+
+    #[test]
+    fn test_protocol_info_delta() {
+        let info = |tvl: &str, volume: &str, fees: &str, revenue: &str| -> ProtocolInfo {
+            serde_json::from_value(json!({
+                "tvl": tvl,
+                "volume24hUsdc": volume,
+                "fees24hUsdc": fees,
+                "revenue24hUsdc": revenue
+            }))
+            .unwrap()
+        };
+        let yesterday = info("200000000", "500000000", "300000", "0");
+        let today = info("230000000", "400000000", "300000", "40000");
+
+        let delta = today.delta(&yesterday).unwrap();
+        assert_eq!(delta.tvl.absolute, Decimal::from(30_000_000));
+        assert_eq!(delta.tvl.percent, Some(Decimal::from(15)));
+        assert_eq!(delta.volume_24h_usdc.absolute, Decimal::from(-100_000_000));
+        assert_eq!(delta.volume_24h_usdc.percent, Some(Decimal::from(-20)));
+        assert_eq!(delta.fees_24h_usdc.absolute, Decimal::ZERO);
+        assert_eq!(delta.fees_24h_usdc.percent, Some(Decimal::ZERO));
+        assert_eq!(delta.revenue_24h_usdc.absolute, Decimal::from(40_000));
+        assert_eq!(delta.revenue_24h_usdc.percent, None);
+    }
+
+    #[test]
+    fn test_lp_opportunity_score() {
+        // 1000 USDC of daily fees and rewards on 100k TVL: 365% APR, halved by the TVL factor.
+        let pool = whirlpool(json!({
+            "tvlUsdc": "100000",
+            "stats": { "24h": { "fees": "600", "rewards": "400" } }
+        }));
+        assert_eq!(
+            pool.lp_opportunity_score().unwrap(),
+            Decimal::from_str("1.825").unwrap()
+        );
+
+        let empty = whirlpool(json!({ "tvlUsdc": "0" }));
+        assert_eq!(empty.lp_opportunity_score().unwrap(), Decimal::ZERO);
+        let no_stats = whirlpool(json!({ "stats": null }));
+        assert_eq!(no_stats.lp_opportunity_score().unwrap(), Decimal::ZERO);
+
+        let huge = whirlpool(json!({ "tvlUsdc": Decimal::MAX.to_string() }));
+        assert!(matches!(
+            huge.lp_opportunity_score(),
+            Err(OrcaError::Overflow("lp_opportunity_score"))
+        ));
+    }
+
+    #[test]
+    fn test_reward_emissions() {
+        let reward: Reward = serde_json::from_value(fixtures::merge(
+            fixtures::reward_json(ORCA_MINT, "0.5"),
+            json!({ "emissions_per_second_x64": ((7u128 << 64) + (1u128 << 62)).to_string() }),
+        ))
+        .unwrap();
+        assert_eq!(
+            reward.emissions_per_second_decimal().unwrap(),
+            Decimal::from_str("0.5").unwrap()
+        );
+        assert_eq!(reward.emissions_per_day().unwrap(), Decimal::from(43_200));
+        assert_eq!(
+            reward.decoded_emissions_per_second_x64().unwrap(),
+            Decimal::from_str("7.25").unwrap()
+        );
+
+        let broken: Reward = serde_json::from_value(fixtures::merge(
+            fixtures::reward_json(ORCA_MINT, "0"),
+            json!({ "emissions_per_second_x64": "-1" }),
+        ))
+        .unwrap();
+        assert!(broken.decoded_emissions_per_second_x64().is_err());
+    }
+
+    #[test]
+    fn test_min_out_with_slippage() {
+        // 1 SOL at 100 USDC with a 0.3% fee.
+        let pool = whirlpool(json!({ "price": "100", "feeRate": 3000 }));
+        let one = Decimal::ONE;
+        let dec = |value: &str| Decimal::from_str(value).unwrap();
+
+        assert_eq!(pool.quote_amount_out(one, true).unwrap(), dec("99.7"));
+        assert_eq!(
+            pool.min_out_with_slippage(one, true, 0).unwrap(),
+            dec("99.7")
+        );
+        assert_eq!(
+            pool.min_out_with_slippage(one, true, 50).unwrap(),
+            dec("99.2015")
+        );
+        assert_eq!(
+            pool.min_out_with_slippage(one, true, 100).unwrap(),
+            dec("98.703")
+        );
+        assert_eq!(
+            pool.min_out_with_slippage(one, true, 10_000).unwrap(),
+            Decimal::ZERO
+        );
+
+        let hundred = Decimal::from(100);
+        assert_eq!(pool.quote_amount_out(hundred, false).unwrap(), dec("0.997"));
+        assert_eq!(
+            pool.min_out_with_slippage(hundred, false, 100).unwrap(),
+            dec("0.98703")
+        );
+
+        assert!(matches!(
+            pool.min_out_with_slippage(one, true, 10_001),
+            Err(OrcaError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn test_active_rewards() {
+        let pool = whirlpool(json!({
+            "rewards": [
+                fixtures::reward_json(ORCA_MINT, "1"),
+                fixtures::merge(fixtures::reward_json(USDC_MINT, "1"), json!({ "active": false })),
+                fixtures::reward_json(DEFAULT_PUBKEY, "0"),
+            ]
+        }));
+        let active = pool.active_rewards();
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].mint, ORCA_MINT);
+        assert!(whirlpool(json!({})).active_rewards().is_empty());
+    }
+
+    #[test]
+    fn test_display() {
+        let pool = whirlpool(json!({ "tvlUsdc": "1234567.89" }));
+        assert_eq!(pool.to_string(), "SOL/USDC 0.04% TVL $1.2M vol24h $300M");
+        let quiet = whirlpool(json!({ "stats": null, "tvlUsdc": "500000", "feeRate": 3000 }));
+        assert_eq!(quiet.to_string(), "SOL/USDC 0.3% TVL $500k");
+
+        let token: Token = serde_json::from_value(fixtures::merge(
+            fixtures::token_json(ORCA_MINT),
+            json!({ "metadata": "{\"symbol\":\"ORCA\"}", "priceUsdc": "1.8512" }),
+        ))
+        .unwrap();
+        assert_eq!(token.to_string(), format!("ORCA {} $1.85", ORCA_MINT));
+
+        let info: ProtocolInfo = serde_json::from_value(json!({
+            "tvl": "230551269.0085",
+            "volume24hUsdc": "1552567794.78",
+            "fees24hUsdc": "317428.05",
+            "revenue24hUsdc": "n/a"
+        }))
+        .unwrap();
+        assert_eq!(
+            info.to_string(),
+            "TVL $230.6M vol24h $1.6B fees24h $317.4k revenue24h n/a"
+        );
+    }
+
+    #[test]
+    fn test_pool_type() {
+        assert_eq!(whirlpool(json!({})).pool_type, PoolType::Concentrated);
+        assert_eq!(
+            whirlpool(json!({ "poolType": "splash" })).pool_type,
+            PoolType::Splash
+        );
+
+        let pool = whirlpool(json!({ "poolType": "wavebreak" }));
+        assert_eq!(pool.pool_type, PoolType::Unknown("wavebreak".to_string()));
+        assert_eq!(pool.pool_type.as_str(), "wavebreak");
+    }
+
+    #[test]
+    fn test_token_parsed_metadata() {
+        let token: Token = serde_json::from_value(fixtures::merge(
+            fixtures::token_json(USDC_MINT),
+            json!({ "metadata": r#"{"name":"USD Coin","symbol":"USDC"}"# }),
+        ))
+        .unwrap();
+        let metadata = token.parsed_metadata().unwrap();
+        assert_eq!(metadata.symbol.as_deref(), Some("USDC"));
+        assert_eq!(metadata.name.as_deref(), Some("USD Coin"));
+        assert_eq!(metadata.image, None);
+
+        let token: Token = serde_json::from_value(fixtures::token_json(USDC_MINT)).unwrap();
+        assert!(token.parsed_metadata().unwrap().symbol.is_none());
+    }
+
+    #[test]
+    fn test_simple_token_info_tags() {
+        let token: SimpleTokenInfo =
+            serde_json::from_value(fixtures::simple_token_json(USDC_MINT, &[])).unwrap();
+        assert!(token.parsed_tags().unwrap().is_empty());
+        assert!(!token.is_verified());
+
+        let token: SimpleTokenInfo = serde_json::from_value(fixtures::simple_token_json(
+            USDC_MINT,
+            &["verified", "stablecoin"],
+        ))
+        .unwrap();
+        assert_eq!(token.parsed_tags().unwrap(), vec!["verified", "stablecoin"]);
+        assert!(token.is_verified());
+
+        let token: SimpleTokenInfo = serde_json::from_value(fixtures::merge(
+            fixtures::simple_token_json(USDC_MINT, &[]),
+            json!({ "tags": "not json" }),
+        ))
+        .unwrap();
+        assert!(token.parsed_tags().is_err());
+        assert!(!token.is_verified());
+    }
+
+    #[test]
+    fn test_whirlpool_is_risky() {
+        let verified = json!(["verified"]).to_string();
+        let pool = whirlpool(json!({
+            "tokenA": { "tags": verified },
+            "tokenB": { "tags": verified }
+        }));
+        assert!(!pool.is_risky());
+
+        let pool = whirlpool(json!({
+            "hasWarning": true,
+            "tokenA": { "tags": verified },
+            "tokenB": { "tags": verified }
+        }));
+        assert!(pool.is_risky());
+
+        let pool = whirlpool(json!({ "tokenA": { "tags": verified } }));
+        assert!(pool.is_risky());
+    }
+
+    #[test]
+    fn test_pubkey() {
+        let key = Pubkey::new(USDC_MINT).unwrap();
+        assert_eq!(key, USDC_MINT);
+        assert_eq!(key.as_str(), USDC_MINT);
+        assert_eq!(key.to_string(), USDC_MINT);
+
+        let malformed = serde_json::from_value::<Pubkey>(json!("not-a-pubkey"));
+        assert_eq!(malformed.is_err(), cfg!(feature = "validate-pubkeys"));
+        let short = Pubkey::new("1111");
+        assert_eq!(short.is_err(), cfg!(feature = "validate-pubkeys"));
+    }
+
+    #[test]
+    fn test_route_price() {
+        let sol_usdc = whirlpool(json!({}));
+        let orca_usdc = whirlpool(json!({
+            "tokenMintA": ORCA_MINT,
+            "tokenMintB": USDC_MINT,
+            "price": "2.5",
+            "feeRate": 3000
+        }));
+
+        // SOL -> USDC at 135.3657 less 0.04%, then USDC -> ORCA at 1 / 2.5 less 0.3%.
+        let price = route_price(&sol_usdc, &orca_usdc, WSOL_MINT, USDC_MINT, ORCA_MINT).unwrap();
+        let expected = Decimal::from_str("135.3657").unwrap()
+            * Decimal::from_str("0.9996").unwrap()
+            / Decimal::from_str("2.5").unwrap()
+            * Decimal::from_str("0.997").unwrap();
+        assert_eq!(price, expected);
+
+        // The two pools do not share ORCA as a middle token.
+        let mismatched = route_price(&sol_usdc, &orca_usdc, WSOL_MINT, ORCA_MINT, USDC_MINT);
+        assert!(matches!(mismatched, Err(OrcaError::Validation(_))));
+    }
+}